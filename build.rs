@@ -1,3 +1,5 @@
+use std::env;
+
 fn add_file(files: &mut Vec<String>, file_path: &str) {
     println!("cargo:rerun-if-changes={file_path}");
     files.push(String::from(file_path));
@@ -11,11 +13,32 @@ fn add_imgui_files(files: &mut Vec<String>, file_name: &str, bb_folder: &str, im
     add_file(files, &cpp);
 }
 
+/// A backend is built when its feature is on and the target has the apis it uses,
+/// the code checks the `backend_*` cfgs instead of repeating both conditions
+fn enabled_backend(name: &str, feature: &str, target_os: &str) -> bool {
+    println!("cargo::rustc-check-cfg=cfg({name})");
+
+    let enabled = env::var_os(format!("CARGO_FEATURE_{}", feature.to_uppercase())).is_some()
+        && env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok(target_os);
+    if enabled {
+        println!("cargo:rustc-cfg={name}");
+    }
+    enabled
+}
+
 fn build_imgui_parts() {
     let mut files = Vec::new();
 
-    add_imgui_files(&mut files, "imgui_impl_win32", "platforms", "backends");
-    add_imgui_files(&mut files, "imgui_impl_dx9", "renderers", "backends");
+    if enabled_backend("backend_win32", "win32", "windows") {
+        add_imgui_files(&mut files, "imgui_impl_win32", "platforms", "backends");
+    }
+    if enabled_backend("backend_dx9", "dx9", "windows") {
+        add_imgui_files(&mut files, "imgui_impl_dx9", "renderers", "backends");
+    }
+
+    if files.is_empty() {
+        return;
+    }
 
     cc::Build::new()
         .files(files)
@@ -4,5 +4,10 @@ mod subbricks;
 
 #[proc_macro_attribute]
 pub fn bluebrick_library(args: TokenStream, item: TokenStream) -> TokenStream {
-    subbricks::bluebrick_library(args, item)
+    subbricks::bluebrick_subbrick(args, item, subbricks::SubBrickKind::Library)
+}
+
+#[proc_macro_attribute]
+pub fn bluebrick_mod(args: TokenStream, item: TokenStream) -> TokenStream {
+    subbricks::bluebrick_subbrick(args, item, subbricks::SubBrickKind::Mod)
 }
@@ -2,11 +2,12 @@ use std::ffi::CString;
 
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse::{Parse, ParseStream}, parse_macro_input, ItemStruct, LitCStr, LitStr, Result, Token};
+use syn::{parse::{Parse, ParseStream}, parse_macro_input, Error, Expr, ExprLit, Ident, Lit, ItemStruct, LitCStr, LitStr, Result, Token};
 
 struct SubBrickNames {
     name: LitStr,
     author: LitStr,
+    color: Option<Expr>,
 }
 
 impl Parse for SubBrickNames {
@@ -14,22 +15,74 @@ impl Parse for SubBrickNames {
         let name = input.parse()?;
         let _ = input.parse::<Token![,]>();
         let author = input.parse()?;
-        Ok(SubBrickNames { name, author })
+
+        let mut color = None;
+        while input.parse::<Token![,]>().is_ok() && !input.is_empty() {
+            let key = input.parse::<Ident>()?;
+            input.parse::<Token![=]>()?;
+            match key.to_string().as_str() {
+                "color" => color = Some(input.parse()?),
+                _ => return Err(Error::new(key.span(), "unknown argument, expected `color`")),
+            }
+        }
+
+        Ok(SubBrickNames { name, author, color })
     }
 }
 
-pub fn bluebrick_library(args: TokenStream, item: TokenStream) -> TokenStream {
+/// Which trait the brick has to implement, the exports are the same for both
+pub enum SubBrickKind {
+    Library,
+    Mod,
+}
+
+pub fn bluebrick_subbrick(args: TokenStream, item: TokenStream, kind: SubBrickKind) -> TokenStream {
     let SubBrickNames {
         name,
         author,
+        color,
     } = parse_macro_input!(args as SubBrickNames);
 
+    // each kind logs through its own part of the host API, so its levels and filters match
+    let logger = match kind {
+        SubBrickKind::Library => quote! { bluebrick::logger::LibraryLogger },
+        SubBrickKind::Mod => quote! { bluebrick::logger::ModLogger },
+    };
+
+    // colors can also be given as css strings, like "cornflowerblue" or "#6495ed".
+    // The logger is made in the exported `new`, so a string that isn't a color is warned about instead of panicking across the FFI.
+    let new_logger = match color {
+        None => quote! { #logger::new(#name) },
+        Some(Expr::Lit(ExprLit { lit: Lit::Str(color), .. })) => quote! {
+            match bluebrick::color::Color::parse(#color) {
+                Ok(color) => #logger::new(#name).with_name_color(color),
+                Err(e) => {
+                    let logger = #logger::new(#name);
+                    bluebrick::logger::Logger::log_warning(&logger, &format!("the name color {e}, so the name is left uncolored"));
+                    logger
+                }
+            }
+        },
+        Some(color) => quote! { #logger::new(#name).with_name_color(#color) },
+    };
+
     let name_cstr = LitCStr::new(&CString::new(name.value()).unwrap(), name.span());
     let author_cstr = LitCStr::new(&CString::new(author.value()).unwrap(), author.span());
 
     let lib = parse_macro_input!(item as ItemStruct);
     let lib_name = &lib.ident;
 
+    let is_kind = match kind {
+        SubBrickKind::Library => quote! {
+            trait IsLibrary : bluebrick::subbrick::Library {}
+            impl IsLibrary for #lib_name {}
+        },
+        SubBrickKind::Mod => quote! {
+            trait IsMod : bluebrick::subbrick::Mod {}
+            impl IsMod for #lib_name {}
+        },
+    };
+
     let result = quote! {
         use bluebrick::logger::HasLogger; // this pollution is necessary for ease of use
 
@@ -53,7 +106,13 @@ pub fn bluebrick_library(args: TokenStream, item: TokenStream) -> TokenStream {
             }
 
             #[unsafe(no_mangle)]
-            extern "C" fn new() -> *mut std::ffi::c_void { Box::into_raw(Box::new(#lib_name::new())) as _ }
+            extern "C" fn set_host_api(api: &'static bluebrick::host::HostApi) -> bool { bluebrick::host::set(api) }
+
+            #[unsafe(no_mangle)]
+            extern "C" fn new() -> *mut std::ffi::c_void {
+                bluebrick::bridge::install(#lib_name::logger());
+                Box::into_raw(Box::new(#lib_name::new())) as _
+            }
 
             fn cast(lib: *mut std::ffi::c_void) -> &'static mut #lib_name { unsafe { &mut *(lib as *mut #lib_name) } }
 
@@ -71,6 +130,19 @@ pub fn bluebrick_library(args: TokenStream, item: TokenStream) -> TokenStream {
 
             #[unsafe(no_mangle)]
             extern "C" fn draw(lib: *mut std::ffi::c_void, ui: &bluebrick::imgui::Ui) { cast(lib).draw(ui); }
+
+            #[unsafe(no_mangle)]
+            extern "C" fn build_menu(lib: *mut std::ffi::c_void, builder: &bluebrick::menu::MenuBuilder) {
+                let mut menu = bluebrick::menu::BrickMenu::new();
+                cast(lib).build_menu(&mut menu);
+                menu.send(builder);
+            }
+
+            #[unsafe(no_mangle)]
+            extern "C" fn menu_clicked(lib: *mut std::ffi::c_void, id: bluebrick::menu::MenuId, checked: bool) { cast(lib).menu_clicked(id, checked); }
+
+            #[unsafe(no_mangle)]
+            extern "C" fn draw_window(lib: *mut std::ffi::c_void, id: bluebrick::menu::MenuId, ui: &bluebrick::imgui::Ui, opened: &mut bool) { cast(lib).draw_window(id, ui, opened); }
         };
 
         #[repr(C)]
@@ -78,13 +150,12 @@ pub fn bluebrick_library(args: TokenStream, item: TokenStream) -> TokenStream {
 
         impl HasLogger for #lib_name {
             fn logger() -> &'static impl bluebrick::logger::Logger {
-                static LOGGER: std::sync::OnceLock<bluebrick::logger::LibraryLogger> = std::sync::OnceLock::new();
-                LOGGER.get_or_init(|| bluebrick::logger::LibraryLogger::new(#name))
+                static LOGGER: std::sync::OnceLock<#logger> = std::sync::OnceLock::new();
+                LOGGER.get_or_init(|| #new_logger)
             }
         }
 
-        trait IsLibrary : bluebrick::subbrick::Library {}
-        impl IsLibrary for #lib_name {}
+        #is_kind
     };
 
     TokenStream::from(result)
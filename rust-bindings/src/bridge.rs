@@ -0,0 +1,110 @@
+use std::fmt::{self, Write};
+
+use tracing::{Event, Metadata, Subscriber, field::{Field, Visit}, subscriber::Interest};
+use tracing_subscriber::{Layer, layer::{Context, SubscriberExt}, registry::Registry};
+
+use crate::logger::{Logger, Severity};
+
+type BrickLogger = dyn Logger + Send + Sync;
+
+fn severity_from_log(level: log::Level) -> Severity {
+    match level {
+        log::Level::Error => Severity::Error,
+        log::Level::Warn => Severity::Warning,
+        log::Level::Info => Severity::Info,
+        log::Level::Debug => Severity::Debug,
+        log::Level::Trace => Severity::Trace,
+    }
+}
+
+fn severity_from_tracing(level: &tracing::Level) -> Severity {
+    match *level {
+        tracing::Level::ERROR => Severity::Error,
+        tracing::Level::WARN => Severity::Warning,
+        tracing::Level::INFO => Severity::Info,
+        tracing::Level::DEBUG => Severity::Debug,
+        _ => Severity::Trace,
+    }
+}
+
+/// Forwards records from the `log` facade to a brick's logger
+pub struct LogBridge {
+    logger: &'static BrickLogger,
+}
+
+impl log::Log for LogBridge {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.logger.enabled(severity_from_log(metadata.level()))
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !log::Log::enabled(self, record.metadata()) {
+            return;
+        }
+
+        let source = record.module_path().unwrap_or(record.target());
+        self.logger.log_with_severity(&format!("[{source}] {}", record.args()), severity_from_log(record.level()));
+    }
+
+    fn flush(&self) {}
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+}
+
+/// Forwards events from `tracing` to a brick's logger
+pub struct TracingBridge {
+    logger: &'static BrickLogger,
+}
+
+impl<S: Subscriber> Layer<S> for TracingBridge {
+    // the level can change at runtime, so callsites can't be cached as always or never enabled
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+        self.logger.enabled(severity_from_tracing(metadata.level()))
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        let source = metadata.module_path().unwrap_or(metadata.target());
+        self.logger.log_with_severity(&format!("[{source}] {}{}", visitor.message, visitor.fields), severity_from_tracing(metadata.level()));
+    }
+}
+
+/// Routes `log` and `tracing` output from the brick and its dependencies to its logger,
+/// called by the brick macros so bricks don't need to do it themselves
+pub fn install(logger: &'static BrickLogger) {
+    if log::set_boxed_logger(Box::new(LogBridge { logger })).is_ok() {
+        log::set_max_level(log::LevelFilter::Trace);
+    }
+
+    let subscriber = Registry::default().with(TracingBridge { logger });
+    let _ = tracing::subscriber::set_global_default(subscriber);
+}
@@ -0,0 +1,281 @@
+use std::{error::Error, fmt, str::FromStr};
+
+use crate::web_colors::WebColor;
+
+/// A 32 bit RGBA color that can be passed across the FFI
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+/// Resets the terminal color after [`Color::ansi_fg`] or [`Color::ansi_fg_16`]
+pub const ANSI_RESET: &str = "\x1b[0m";
+
+/// The 16 standard terminal colors, as xterm draws them
+const ANSI_16: [Color; 16] = [
+    Color::rgb(0, 0, 0),
+    Color::rgb(205, 0, 0),
+    Color::rgb(0, 205, 0),
+    Color::rgb(205, 205, 0),
+    Color::rgb(0, 0, 238),
+    Color::rgb(205, 0, 205),
+    Color::rgb(0, 205, 205),
+    Color::rgb(229, 229, 229),
+    Color::rgb(127, 127, 127),
+    Color::rgb(255, 0, 0),
+    Color::rgb(0, 255, 0),
+    Color::rgb(255, 255, 0),
+    Color::rgb(92, 92, 255),
+    Color::rgb(255, 0, 255),
+    Color::rgb(0, 255, 255),
+    Color::rgb(255, 255, 255),
+];
+
+impl Color {
+    pub const TRANSPARENT: Color = Color::rgba(0, 0, 0, 0);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: u8::MAX }
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    pub const fn with_alpha(self, a: u8) -> Self {
+        Self { a, ..self }
+    }
+
+    /// Hue is in degrees, saturation, lightness and alpha are from 0 to 1
+    pub fn from_hsla(hue: f32, saturation: f32, lightness: f32, alpha: f32) -> Self {
+        let hue = hue.rem_euclid(360.0) / 60.0;
+        let saturation = saturation.clamp(0.0, 1.0);
+        let lightness = lightness.clamp(0.0, 1.0);
+
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let (r, g, b) = match hue as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = lightness - chroma / 2.0;
+
+        Self::rgba(unit_to_u8(r + m), unit_to_u8(g + m), unit_to_u8(b + m), unit_to_u8(alpha))
+    }
+
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Self {
+        Self::from_hsla(hue, saturation, lightness, 1.0)
+    }
+
+    /// Returns hue in degrees, saturation and lightness from 0 to 1
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let [r, g, b, _] = self.to_imgui();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let lightness = (max + min) / 2.0;
+        let delta = max - min;
+
+        if delta == 0.0 {
+            return (0.0, 0.0, lightness);
+        }
+
+        let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+        let hue = if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+
+        (hue, saturation, lightness)
+    }
+
+    /// Parses a CSS color: a name like `cornflowerblue`, `#rgb`, `#rgba`, `#rrggbb`, `#rrggbbaa`,
+    /// `rgb(…)`, `rgba(…)`, `hsl(…)` or `hsla(…)`
+    pub fn parse(string: &str) -> Result<Self, ParseColorError> {
+        let string = string.trim();
+        let error = || ParseColorError(String::from(string));
+
+        if let Some(hex) = string.strip_prefix('#') {
+            return Self::parse_hex(hex).ok_or_else(error);
+        }
+
+        if let Some((function, args)) = string.strip_suffix(')').and_then(|string| string.split_once('(')) {
+            return Self::parse_function(&function.trim().to_ascii_lowercase(), args).ok_or_else(error);
+        }
+
+        if string.eq_ignore_ascii_case("transparent") {
+            return Ok(Self::TRANSPARENT);
+        }
+
+        WebColor::from_name(string).map(Self::from).ok_or_else(error)
+    }
+
+    fn parse_hex(hex: &str) -> Option<Self> {
+        if !hex.is_ascii() {
+            return None;
+        }
+
+        let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|digit| digit * 0x11);
+        let pair = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+
+        match hex.len() {
+            3 => Some(Self::rgb(digit(0)?, digit(1)?, digit(2)?)),
+            4 => Some(Self::rgba(digit(0)?, digit(1)?, digit(2)?, digit(3)?)),
+            6 => Some(Self::rgb(pair(0)?, pair(2)?, pair(4)?)),
+            8 => Some(Self::rgba(pair(0)?, pair(2)?, pair(4)?, pair(6)?)),
+            _ => None,
+        }
+    }
+
+    fn parse_function(function: &str, args: &str) -> Option<Self> {
+        let args = args.split([',', ' ', '/']).map(str::trim).filter(|arg| !arg.is_empty()).collect::<Vec<_>>();
+        let alpha = match args.get(3) {
+            Some(alpha) => parse_unit(alpha)?,
+            None if args.len() == 3 => 1.0,
+            None => return None,
+        };
+        if args.len() > 4 {
+            return None;
+        }
+
+        match function {
+            "rgb" | "rgba" => {
+                let channel = |arg: &str| match arg.strip_suffix('%') {
+                    Some(percent) => percent.parse::<f32>().ok().map(|percent| unit_to_u8(percent / 100.0)),
+                    None => arg.parse::<f32>().ok().map(|value| value.round().clamp(0.0, 255.0) as u8),
+                };
+                Some(Self::rgba(channel(args[0])?, channel(args[1])?, channel(args[2])?, unit_to_u8(alpha)))
+            }
+            "hsl" | "hsla" => {
+                let hue = args[0].strip_suffix("deg").unwrap_or(args[0]).parse().ok()?;
+                let percent = |arg: &str| arg.strip_suffix('%')?.parse::<f32>().ok().map(|percent| percent / 100.0);
+                Some(Self::from_hsla(hue, percent(args[1])?, percent(args[2])?, alpha))
+            }
+            _ => None,
+        }
+    }
+
+    /// Formats as `#rrggbb`, or `#rrggbbaa` if it isn't opaque
+    pub fn to_hex(self) -> String {
+        match self.a {
+            u8::MAX => format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b),
+            a => format!("#{:02x}{:02x}{:02x}{a:02x}", self.r, self.g, self.b),
+        }
+    }
+
+    /// The color as imgui takes it, with every channel from 0 to 1
+    pub fn to_imgui(self) -> [f32; 4] {
+        [u8_to_unit(self.r), u8_to_unit(self.g), u8_to_unit(self.b), u8_to_unit(self.a)]
+    }
+
+    /// The escape code that sets the terminal's text to this color, for terminals that support 24 bit color
+    pub fn ansi_fg(self) -> String {
+        format!("\x1b[38;2;{};{};{}m", self.r, self.g, self.b)
+    }
+
+    /// The escape code that sets the terminal's text to the closest of the 16 standard colors
+    pub fn ansi_fg_16(self) -> String {
+        match self.closest_ansi_16() {
+            index @ 0..8 => format!("\x1b[{}m", 30 + index),
+            index => format!("\x1b[{}m", 90 + index - 8),
+        }
+    }
+
+    /// The index of the closest of the 16 standard terminal colors
+    pub fn closest_ansi_16(self) -> u8 {
+        let distance = |other: &Color| {
+            let dr = self.r as i32 - other.r as i32;
+            let dg = self.g as i32 - other.g as i32;
+            let db = self.b as i32 - other.b as i32;
+            dr * dr + dg * dg + db * db
+        };
+
+        ANSI_16.iter().enumerate().min_by_key(|(_, color)| distance(color)).map(|(index, _)| index as u8).unwrap_or_default()
+    }
+
+    /// Wraps the text in escape codes to draw it in this color
+    pub fn paint(self, text: &str) -> String {
+        format!("{}{text}{ANSI_RESET}", self.ansi_fg())
+    }
+}
+
+fn u8_to_unit(byte: u8) -> f32 {
+    byte as f32 / u8::MAX as f32
+}
+
+fn unit_to_u8(unit: f32) -> u8 {
+    (unit.clamp(0.0, 1.0) * u8::MAX as f32).round() as u8
+}
+
+/// Parses an alpha value, either from 0 to 1 or as a percentage
+fn parse_unit(arg: &str) -> Option<f32> {
+    match arg.strip_suffix('%') {
+        Some(percent) => percent.parse::<f32>().ok().map(|percent| percent / 100.0),
+        None => arg.parse().ok(),
+    }
+}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Self::parse(string)
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+impl From<[u8; 3]> for Color {
+    fn from([r, g, b]: [u8; 3]) -> Self {
+        Self::rgb(r, g, b)
+    }
+}
+
+impl From<[u8; 4]> for Color {
+    fn from([r, g, b, a]: [u8; 4]) -> Self {
+        Self::rgba(r, g, b, a)
+    }
+}
+
+impl From<[f32; 4]> for Color {
+    fn from([r, g, b, a]: [f32; 4]) -> Self {
+        Self::rgba(unit_to_u8(r), unit_to_u8(g), unit_to_u8(b), unit_to_u8(a))
+    }
+}
+
+impl From<Color> for [f32; 4] {
+    fn from(color: Color) -> Self {
+        color.to_imgui()
+    }
+}
+
+impl From<Color> for imgui::ImColor32 {
+    fn from(color: Color) -> Self {
+        imgui::ImColor32::from_rgba(color.r, color.g, color.b, color.a)
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseColorError(String);
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} is not a color name, hex code, rgb() or hsl() color", self.0)
+    }
+}
+
+impl Error for ParseColorError {}
@@ -0,0 +1,78 @@
+use std::{ffi::{CStr, c_char}, path::PathBuf, sync::{OnceLock, atomic::AtomicU8}};
+
+use crate::{color::Color, logger::Severity, web_colors::Rgb};
+
+/// Bumped whenever fields are added to the end of [`HostApi`], bricks accept any table at least this new
+pub const HOST_API_VERSION: u32 = 5;
+
+/// Kept for bricks built before [`Color`], new bricks use [`ColorLogFn`]
+pub type LogFn = extern "C" fn(name: *const c_char, name_color: Option<&Rgb>, msg: *const c_char, severity: Severity);
+/// Hands the loader a brick's minimum level, which it writes the rank of a [`Severity`] to whenever it changes
+pub type RegisterLevelFn = extern "C" fn(name: *const c_char, min_level: &'static AtomicU8);
+/// Kept for bricks built before [`Color`], new bricks use [`ColorNotifyFn`]
+pub type NotifyFn = extern "C" fn(name: *const c_char, name_color: Option<&Rgb>, title: *const c_char, msg: *const c_char, severity: Severity, sticky: bool);
+pub type ColorLogFn = extern "C" fn(name: *const c_char, name_color: Option<&Color>, msg: *const c_char, severity: Severity);
+pub type ColorNotifyFn = extern "C" fn(name: *const c_char, name_color: Option<&Color>, title: *const c_char, msg: *const c_char, severity: Severity, sticky: bool);
+
+/// The services the loader provides to bricks, handed to each brick before it is created.
+/// Fields are only ever added to the end, so older bricks keep working with newer loaders.
+#[repr(C)]
+pub struct HostApi {
+    pub version: u32,
+    pub loader_version: *const c_char,
+
+    pub log_library: LogFn,
+    pub log_mod: LogFn,
+    pub notify_library: NotifyFn,
+    pub notify_mod: NotifyFn,
+
+    /// The absolute path of the folder BlueBrick is installed in
+    pub bluebrick_dir: extern "C" fn() -> *const c_char,
+
+    pub register_library_level: RegisterLevelFn,
+    pub register_mod_level: RegisterLevelFn,
+
+    /// The absolute path of one of BlueBrick's folders by its [`PathKind`](crate::paths::PathKind), null for kinds the loader doesn't know
+    pub dir: extern "C" fn(kind: u32) -> *const c_char,
+
+    pub log_library_color: ColorLogFn,
+    pub log_mod_color: ColorLogFn,
+    pub notify_library_color: ColorNotifyFn,
+    pub notify_mod_color: ColorNotifyFn,
+}
+
+// the table is immutable and only holds static strings and function pointers
+unsafe impl Sync for HostApi {}
+unsafe impl Send for HostApi {}
+
+static HOST_API: OnceLock<&'static HostApi> = OnceLock::new();
+
+fn to_string(string: *const c_char) -> String {
+    let string = unsafe { CStr::from_ptr(string) };
+    String::from_utf8_lossy(string.to_bytes()).to_string()
+}
+
+/// Called by the brick macros, returns false if the loader is too old for this brick
+pub fn set(api: &'static HostApi) -> bool {
+    if api.version < HOST_API_VERSION {
+        return false;
+    }
+    let _ = HOST_API.set(api);
+    true
+}
+
+pub fn get() -> &'static HostApi {
+    HOST_API.get().expect("BlueBrick host API is not set, bricks must be loaded by BlueBrick")
+}
+
+pub fn loader_version() -> String {
+    to_string(get().loader_version)
+}
+
+pub(crate) fn to_path(string: *const c_char) -> PathBuf {
+    PathBuf::from(to_string(string))
+}
+
+pub fn bluebrick_dir() -> PathBuf {
+    to_path((get().bluebrick_dir)())
+}
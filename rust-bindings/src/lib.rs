@@ -1,5 +1,11 @@
+pub mod bridge;
+pub mod color;
+pub mod host;
 pub mod logger;
+pub mod menu;
+pub mod paths;
 pub mod subbrick;
+pub mod web_colors;
 
 pub use bluebrick_proc_macros::*;
 pub use imgui;
@@ -1,21 +1,78 @@
-use std::{ffi::{c_char, CString}, sync::OnceLock};
+use std::{ffi::CString, sync::atomic::{AtomicU8, Ordering}};
 
-use dlopen::wrapper::{Container, WrapperApi};
-use dlopen_derive::WrapperApi;
+use crate::host::{self, ColorLogFn, ColorNotifyFn, RegisterLevelFn};
+use crate::color::Color;
 
 #[repr(C)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Severity {
     Info,
     Debug,
     Warning,
-    Error
+    Error,
+    /// Added after the others so their values across the FFI stay the same
+    Trace,
+}
+
+impl Severity {
+    /// From most to least verbose
+    pub const ALL: [Severity; 5] = [Severity::Trace, Severity::Debug, Severity::Info, Severity::Warning, Severity::Error];
+
+    /// How important the severity is, higher is more important, used to compare against minimum levels
+    pub const fn rank(self) -> u8 {
+        match self {
+            Severity::Trace => 0,
+            Severity::Debug => 1,
+            Severity::Info => 2,
+            Severity::Warning => 3,
+            Severity::Error => 4,
+        }
+    }
+
+    pub fn from_rank(rank: u8) -> Self {
+        Self::ALL[(rank as usize).min(Self::ALL.len() - 1)]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Trace => "trace",
+            Severity::Debug => "debug",
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "trace" => Some(Severity::Trace),
+            "debug" => Some(Severity::Debug),
+            "info" => Some(Severity::Info),
+            "warning" | "warn" => Some(Severity::Warning),
+            "error" => Some(Severity::Error),
+            _ => None,
+        }
+    }
+
+    /// True if a message of this severity passes the minimum level
+    pub fn is_at_least(self, min: Severity) -> bool {
+        self.rank() >= min.rank()
+    }
 }
 
 // TODO: add documentation
 pub trait Logger {
+    /// Whether messages of this severity are currently wanted, checked by the macros before formatting
+    fn enabled(&self, _severity: Severity) -> bool {
+        true
+    }
+
     fn log_with_severity(&self, msg: &str, severity: Severity);
     
+    fn log_trace(&self, msg: &str) {
+        self.log_with_severity(msg, Severity::Trace);
+    }
+
     fn log(&self, msg: &str) {
         self.log_with_severity(msg, Severity::Info);
     }
@@ -31,6 +88,21 @@ pub trait Logger {
     fn log_error(&self, msg: &str) {
         self.log_with_severity(msg, Severity::Error);
     }
+
+    /// Logs the message and shows it as a toast in the overlay, sticky toasts stay until dismissed
+    fn notify_with_severity(&self, title: &str, msg: &str, severity: Severity, sticky: bool);
+
+    fn notify(&self, title: &str, msg: &str) {
+        self.notify_with_severity(title, msg, Severity::Info, false);
+    }
+
+    fn notify_warning(&self, title: &str, msg: &str) {
+        self.notify_with_severity(title, msg, Severity::Warning, false);
+    }
+
+    fn notify_error(&self, title: &str, msg: &str) {
+        self.notify_with_severity(title, msg, Severity::Error, true);
+    }
 }
 
 pub trait HasLogger {
@@ -40,67 +112,179 @@ pub trait HasLogger {
 #[macro_export]
 macro_rules! log {
     ($dst:expr, $($arg:tt)*) => {
-        $dst.log(&format!($($arg)*));
+        if $dst.enabled($crate::logger::Severity::Info) {
+            $dst.log(&format!($($arg)*));
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! log_trace {
+    ($dst:expr, $($arg:tt)*) => {
+        if $dst.enabled($crate::logger::Severity::Trace) {
+            $dst.log_trace(&format!($($arg)*));
+        }
     }
 }
 
 #[macro_export]
 macro_rules! log_debug {
     ($dst:expr, $($arg:tt)*) => {
-        $dst.log_debug(&format!($($arg)*));
+        if $dst.enabled($crate::logger::Severity::Debug) {
+            $dst.log_debug(&format!($($arg)*));
+        }
     }
 }
 
 #[macro_export]
 macro_rules! log_warning {
     ($dst:expr, $($arg:tt)*) => {
-        $dst.log_warning(&format!($($arg)*));
+        if $dst.enabled($crate::logger::Severity::Warning) {
+            $dst.log_warning(&format!($($arg)*));
+        }
     }
 }
 
 #[macro_export]
 macro_rules! log_error {
     ($dst:expr, $($arg:tt)*) => {
-        $dst.log_error(&format!($($arg)*));
+        if $dst.enabled($crate::logger::Severity::Error) {
+            $dst.log_error(&format!($($arg)*));
+        }
     }
 }
 
-#[derive(WrapperApi)]
-struct BBLoggerApi {
-    log_library_impl: extern "C" fn (name: *const c_char, msg: *const c_char, severity: Severity),
-    log_mod_impl: extern "C" fn (name: *const c_char, msg: *const c_char, severity: Severity),
+#[macro_export]
+macro_rules! notify {
+    ($dst:expr, $title:expr, $($arg:tt)*) => {
+        $dst.notify($title, &format!($($arg)*));
+    }
 }
 
-fn get_bb_logger_api() -> &'static Container<BBLoggerApi> {
-    static GET_API: OnceLock<Container<BBLoggerApi>> = OnceLock::new();
-    GET_API.get_or_init(|| {
-        match unsafe { Container::<BBLoggerApi>::load("bluebrick/bluebrick") } {
-            Ok(api) => api,
-            Err(e) => panic!("{e}")
-        }
-    })
+#[macro_export]
+macro_rules! notify_warning {
+    ($dst:expr, $title:expr, $($arg:tt)*) => {
+        $dst.notify_warning($title, &format!($($arg)*));
+    }
 }
 
-pub struct LibraryLogger {
+#[macro_export]
+macro_rules! notify_error {
+    ($dst:expr, $title:expr, $($arg:tt)*) => {
+        $dst.notify_error($title, &format!($($arg)*));
+    }
+}
+
+/// Sends a brick's logs and notifications to the loader through the host API
+struct BrickLogger {
     name: &'static str,
-    log_impl: extern "C" fn(*const c_char, *const c_char, Severity),
+    name_color: Option<Color>,
+    log_impl: ColorLogFn,
+    notify_impl: ColorNotifyFn,
+    /// The rank of the minimum severity, kept up to date by the loader so checking it never crosses the FFI
+    min_level: &'static AtomicU8,
 }
 
-impl LibraryLogger {
-    pub fn new(name: &'static str) -> Self {
+impl BrickLogger {
+    fn new(name: &'static str, register_level: RegisterLevelFn, log_impl: ColorLogFn, notify_impl: ColorNotifyFn) -> Self {
+        let min_level = Box::leak(Box::new(AtomicU8::new(Severity::Trace.rank())));
+        let c_name = CString::new(name.replace("\0", "")).unwrap();
+        register_level(c_name.as_ptr(), min_level);
+
         Self {
             name,
-            log_impl: get_bb_logger_api().log_library_impl
+            name_color: None,
+            log_impl,
+            notify_impl,
+            min_level,
         }
     }
 }
 
-impl Logger for LibraryLogger {
+impl Logger for BrickLogger {
+    fn enabled(&self, severity: Severity) -> bool {
+        severity.rank() >= self.min_level.load(Ordering::Relaxed)
+    }
+
     fn log_with_severity(&self, msg: &str, severity: Severity) {
+        if !self.enabled(severity) {
+            return;
+        }
+
         let name = self.name.replace("\0", "");
         let name = CString::new(name).unwrap();
         let msg = msg.replace("\0", "");
         let msg = CString::new(msg).unwrap();
-        (self.log_impl)(name.as_ptr(), msg.as_ptr(), severity);
+        (self.log_impl)(name.as_ptr(), self.name_color.as_ref(), msg.as_ptr(), severity);
+    }
+
+    fn notify_with_severity(&self, title: &str, msg: &str, severity: Severity, sticky: bool) {
+        let name = self.name.replace("\0", "");
+        let name = CString::new(name).unwrap();
+        let title = title.replace("\0", "");
+        let title = CString::new(title).unwrap();
+        let msg = msg.replace("\0", "");
+        let msg = CString::new(msg).unwrap();
+        (self.notify_impl)(name.as_ptr(), self.name_color.as_ref(), title.as_ptr(), msg.as_ptr(), severity, sticky);
+    }
+}
+
+/// The logger of bricks made with `#[bluebrick_library]`, its levels are set with `library:Name=level`
+pub struct LibraryLogger(BrickLogger);
+
+impl LibraryLogger {
+    pub fn new(name: &'static str) -> Self {
+        let host = host::get();
+        Self(BrickLogger::new(name, host.register_library_level, host.log_library_color, host.notify_library_color))
+    }
+
+    /// Sets the color the library's name is shown with in the logs
+    pub fn with_name_color(mut self, color: impl Into<Color>) -> Self {
+        self.0.name_color = Some(color.into());
+        self
+    }
+}
+
+impl Logger for LibraryLogger {
+    fn enabled(&self, severity: Severity) -> bool {
+        self.0.enabled(severity)
+    }
+
+    fn log_with_severity(&self, msg: &str, severity: Severity) {
+        self.0.log_with_severity(msg, severity);
+    }
+
+    fn notify_with_severity(&self, title: &str, msg: &str, severity: Severity, sticky: bool) {
+        self.0.notify_with_severity(title, msg, severity, sticky);
+    }
+}
+
+/// The logger of bricks made with `#[bluebrick_mod]`, its levels are set with `mod:Name=level`
+pub struct ModLogger(BrickLogger);
+
+impl ModLogger {
+    pub fn new(name: &'static str) -> Self {
+        let host = host::get();
+        Self(BrickLogger::new(name, host.register_mod_level, host.log_mod_color, host.notify_mod_color))
+    }
+
+    /// Sets the color the mod's name is shown with in the logs
+    pub fn with_name_color(mut self, color: impl Into<Color>) -> Self {
+        self.0.name_color = Some(color.into());
+        self
+    }
+}
+
+impl Logger for ModLogger {
+    fn enabled(&self, severity: Severity) -> bool {
+        self.0.enabled(severity)
+    }
+
+    fn log_with_severity(&self, msg: &str, severity: Severity) {
+        self.0.log_with_severity(msg, severity);
+    }
+
+    fn notify_with_severity(&self, title: &str, msg: &str, severity: Severity, sticky: bool) {
+        self.0.notify_with_severity(title, msg, severity, sticky);
     }
 }
@@ -0,0 +1,212 @@
+use std::{ffi::{CStr, CString, c_char, c_void}, ptr};
+
+/// Identifies one of a brick's menu entries, chosen by the brick and unique within it
+pub type MenuId = u32;
+
+// the kinds of entry sent to the loader, MENU_END closes the last submenu
+const MENU_ITEM: u32 = 0;
+const MENU_TOGGLE: u32 = 1;
+const MENU_WINDOW: u32 = 2;
+const MENU_SEPARATOR: u32 = 3;
+const MENU_SUBMENU: u32 = 4;
+const MENU_END: u32 = 5;
+
+/// One entry as a brick sends it to the loader, the strings only live for the call
+#[repr(C)]
+pub struct RawMenuEntry {
+    kind: u32,
+    id: MenuId,
+    label: *const c_char,
+    /// Null when the entry has no shortcut
+    shortcut: *const c_char,
+    /// Whether a toggle is checked or a window is open
+    checked: bool,
+}
+
+/// Made by the loader and handed to a brick's `build_menu`, which sends it its entries in order
+#[repr(C)]
+pub struct MenuBuilder {
+    menu: *mut c_void,
+    add: extern "C" fn(menu: *mut c_void, entry: &RawMenuEntry),
+}
+
+pub enum MenuEntryKind {
+    /// A plain item, reported to the brick every time it is clicked
+    Item,
+    /// A checkable item, its checked state is kept by the loader
+    Toggle { checked: bool },
+    /// A checkable item that opens and closes one of the brick's windows
+    Window { open: bool },
+    Separator,
+    SubMenu(BrickMenu),
+}
+
+pub struct MenuEntry {
+    /// None for separators and submenus, which are never clicked
+    pub id: Option<MenuId>,
+    pub label: String,
+    pub shortcut: Option<String>,
+    pub kind: MenuEntryKind,
+}
+
+/// The entries a brick registers under its own submenu of the "Bricks" menu
+#[derive(Default)]
+pub struct BrickMenu {
+    pub entries: Vec<MenuEntry>,
+}
+
+impl BrickMenu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn push(&mut self, id: Option<MenuId>, label: &str, kind: MenuEntryKind) -> &mut MenuEntry {
+        self.entries.push(MenuEntry {
+            id,
+            label: String::from(label),
+            shortcut: None,
+            kind,
+        });
+        self.entries.last_mut().unwrap()
+    }
+
+    pub fn item(&mut self, id: MenuId, label: &str) -> &mut MenuEntry {
+        self.push(Some(id), label, MenuEntryKind::Item)
+    }
+
+    pub fn toggle(&mut self, id: MenuId, label: &str, checked: bool) -> &mut MenuEntry {
+        self.push(Some(id), label, MenuEntryKind::Toggle { checked })
+    }
+
+    pub fn window(&mut self, id: MenuId, label: &str, open: bool) -> &mut MenuEntry {
+        self.push(Some(id), label, MenuEntryKind::Window { open })
+    }
+
+    pub fn separator(&mut self) {
+        self.push(None, "", MenuEntryKind::Separator);
+    }
+
+    pub fn submenu<F: FnOnce(&mut BrickMenu)>(&mut self, label: &str, f: F) {
+        let mut submenu = BrickMenu::new();
+        f(&mut submenu);
+        self.push(None, label, MenuEntryKind::SubMenu(submenu));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Finds an entry by id, searching submenus as well
+    pub fn find_mut(&mut self, id: MenuId) -> Option<&mut MenuEntry> {
+        for entry in &mut self.entries {
+            if entry.id == Some(id) {
+                return Some(entry);
+            }
+            if let MenuEntryKind::SubMenu(submenu) = &mut entry.kind
+                && let Some(found) = submenu.find_mut(id)
+            {
+                return Some(found);
+            }
+        }
+        None
+    }
+
+    /// Calls `f` on every window entry, including those in submenus
+    pub fn for_each_window<F: FnMut(MenuId, &mut bool)>(&mut self, f: &mut F) {
+        for entry in &mut self.entries {
+            match (&mut entry.kind, entry.id) {
+                (MenuEntryKind::Window { open }, Some(id)) => f(id, open),
+                (MenuEntryKind::SubMenu(submenu), _) => submenu.for_each_window(f),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl MenuEntry {
+    pub fn shortcut(&mut self, shortcut: &str) -> &mut Self {
+        self.shortcut = Some(String::from(shortcut));
+        self
+    }
+}
+
+impl BrickMenu {
+    /// Sends the entries to the loader through `builder`, called by the brick macros' `build_menu`
+    pub fn send(&self, builder: &MenuBuilder) {
+        for entry in &self.entries {
+            // a label with a nul in it can't be sent, so it is sent empty
+            let label = CString::new(entry.label.as_str()).unwrap_or_default();
+            let shortcut = entry.shortcut.as_deref().map(|shortcut| CString::new(shortcut).unwrap_or_default());
+            let (kind, checked) = match &entry.kind {
+                MenuEntryKind::Item => (MENU_ITEM, false),
+                MenuEntryKind::Toggle { checked } => (MENU_TOGGLE, *checked),
+                MenuEntryKind::Window { open } => (MENU_WINDOW, *open),
+                MenuEntryKind::Separator => (MENU_SEPARATOR, false),
+                MenuEntryKind::SubMenu(_) => (MENU_SUBMENU, false),
+            };
+
+            let raw = RawMenuEntry {
+                kind,
+                id: entry.id.unwrap_or_default(),
+                label: label.as_ptr(),
+                shortcut: shortcut.as_ref().map_or(ptr::null(), |shortcut| shortcut.as_ptr()),
+                checked,
+            };
+            (builder.add)(builder.menu, &raw);
+
+            if let MenuEntryKind::SubMenu(submenu) = &entry.kind {
+                submenu.send(builder);
+                (builder.add)(builder.menu, &RawMenuEntry { kind: MENU_END, id: 0, label: c"".as_ptr(), shortcut: ptr::null(), checked: false });
+            }
+        }
+    }
+
+    /// Builds the menu a brick sends from `build`, used by the loader.
+    /// Entries of kinds this version doesn't know are left out.
+    pub fn receive<F: FnOnce(&MenuBuilder)>(build: F) -> Self {
+        // the menus being filled, with the label of each open submenu
+        let mut open: Vec<(String, BrickMenu)> = vec![(String::new(), BrickMenu::new())];
+
+        extern "C" fn add(menu: *mut c_void, entry: &RawMenuEntry) {
+            let open = unsafe { &mut *(menu as *mut Vec<(String, BrickMenu)>) };
+            let read = |string: *const c_char| match string.is_null() {
+                true => None,
+                false => Some(String::from_utf8_lossy(unsafe { CStr::from_ptr(string) }.to_bytes()).into_owned()),
+            };
+            let label = read(entry.label).unwrap_or_default();
+
+            let kind = match entry.kind {
+                MENU_ITEM => MenuEntryKind::Item,
+                MENU_TOGGLE => MenuEntryKind::Toggle { checked: entry.checked },
+                MENU_WINDOW => MenuEntryKind::Window { open: entry.checked },
+                MENU_SEPARATOR => MenuEntryKind::Separator,
+                MENU_SUBMENU => return open.push((label, BrickMenu::new())),
+                MENU_END => return close_submenu(open),
+                _ => return,
+            };
+            let id = match kind {
+                MenuEntryKind::Separator => None,
+                _ => Some(entry.id),
+            };
+
+            let (_, menu) = open.last_mut().unwrap();
+            menu.push(id, &label, kind).shortcut = read(entry.shortcut);
+        }
+
+        fn close_submenu(open: &mut Vec<(String, BrickMenu)>) {
+            if open.len() > 1 {
+                let (label, submenu) = open.pop().unwrap();
+                let (_, menu) = open.last_mut().unwrap();
+                menu.push(None, &label, MenuEntryKind::SubMenu(submenu));
+            }
+        }
+
+        build(&MenuBuilder { menu: &mut open as *mut _ as *mut c_void, add });
+
+        // submenus the brick didn't close are closed for it
+        while open.len() > 1 {
+            close_submenu(&mut open);
+        }
+        open.pop().unwrap().1
+    }
+}
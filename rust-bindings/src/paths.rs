@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use crate::host;
+
+/// The folders the loader resolved, see [`dir`]
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PathKind {
+    /// The folder BlueBrick is installed in
+    Root,
+    /// Where `config.toml` and the overlay's settings are
+    Config,
+    /// Where bricks keep their files. Every brick shares it, so keep yours in a folder named after your brick.
+    Data,
+    /// Files that can be deleted at any time. Every brick shares it, so keep yours in a folder named after your brick.
+    Cache,
+    /// Logs and crash reports
+    Logs,
+}
+
+impl PathKind {
+    pub const ALL: [PathKind; 5] = [PathKind::Root, PathKind::Config, PathKind::Data, PathKind::Cache, PathKind::Logs];
+
+    /// The kind passed across the FFI as a number, so a kind the other side doesn't know can be turned away
+    pub fn from_u32(kind: u32) -> Option<Self> {
+        Self::ALL.get(kind as usize).copied()
+    }
+}
+
+/// The absolute path of one of BlueBrick's folders, which might not exist yet
+pub fn dir(kind: PathKind) -> PathBuf {
+    let path = (host::get().dir)(kind as u32);
+    assert!(!path.is_null(), "the loader doesn't know the {kind:?} folder");
+    host::to_path(path)
+}
+
+pub fn root() -> PathBuf {
+    dir(PathKind::Root)
+}
+
+pub fn config_dir() -> PathBuf {
+    dir(PathKind::Config)
+}
+
+pub fn data_dir() -> PathBuf {
+    dir(PathKind::Data)
+}
+
+pub fn cache_dir() -> PathBuf {
+    dir(PathKind::Cache)
+}
+
+pub fn log_dir() -> PathBuf {
+    dir(PathKind::Logs)
+}
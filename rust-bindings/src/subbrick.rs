@@ -1,6 +1,7 @@
 use imgui::Ui;
 
 use crate::logger::HasLogger;
+use crate::menu::{BrickMenu, MenuId};
 
 pub trait SubBrick : HasLogger {
     // TODO: add depends on function
@@ -11,6 +12,13 @@ pub trait SubBrick : HasLogger {
     fn disable(&mut self) -> bool;
 
     fn draw(&mut self, ui: &Ui);
+
+    /// Called once after init to register entries under the brick's submenu of the "Bricks" menu
+    fn build_menu(&mut self, _menu: &mut BrickMenu) {}
+    /// Called when an item or toggle is clicked, `checked` is the toggle's new state
+    fn menu_clicked(&mut self, _id: MenuId, _checked: bool) {}
+    /// Called every frame for each window entry the loader has open
+    fn draw_window(&mut self, _id: MenuId, _ui: &Ui, _opened: &mut bool) {}
 }
 
 pub trait Library : SubBrick {
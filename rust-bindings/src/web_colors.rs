@@ -0,0 +1,480 @@
+use crate::color::Color;
+
+/// A 24 bit color that can be passed across the FFI.
+/// Only kept for the host API functions that shipped with it, use [`Color`] instead.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+}
+
+impl From<[u8; 3]> for Rgb {
+    fn from([r, g, b]: [u8; 3]) -> Self {
+        Self { r, g, b }
+    }
+}
+
+impl From<Rgb> for Color {
+    fn from(Rgb { r, g, b }: Rgb) -> Self {
+        Color::rgb(r, g, b)
+    }
+}
+
+/// The named CSS colors
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WebColor {
+    AliceBlue,
+    AntiqueWhite,
+    Aqua,
+    Aquamarine,
+    Azure,
+    Beige,
+    Bisque,
+    Black,
+    BlanchedAlmond,
+    Blue,
+    BlueViolet,
+    Brown,
+    BurlyWood,
+    CadetBlue,
+    Chartreuse,
+    Chocolate,
+    Coral,
+    CornflowerBlue,
+    Cornsilk,
+    Crimson,
+    Cyan,
+    DarkBlue,
+    DarkCyan,
+    DarkGoldenrod,
+    DarkGray,
+    DarkGreen,
+    DarkKhaki,
+    DarkMagenta,
+    DarkOliveGreen,
+    DarkOrange,
+    DarkOrchid,
+    DarkRed,
+    DarkSalmon,
+    DarkSeaGreen,
+    DarkSlateBlue,
+    DarkSlateGray,
+    DarkTurquoise,
+    DarkViolet,
+    DeepPink,
+    DeepSkyBlue,
+    DimGray,
+    DodgerBlue,
+    Firebrick,
+    FloralWhite,
+    ForestGreen,
+    Fuchsia,
+    Gainsboro,
+    GhostWhite,
+    Gold,
+    Goldenrod,
+    Gray,
+    Green,
+    GreenYellow,
+    Honeydew,
+    HotPink,
+    IndianRed,
+    Indigo,
+    Ivory,
+    Khaki,
+    Lavender,
+    LavenderBlush,
+    LawnGreen,
+    LemonChiffon,
+    LightBlue,
+    LightCoral,
+    LightCyan,
+    LightGoldenrodYellow,
+    LightGray,
+    LightGreen,
+    LightPink,
+    LightSalmon,
+    LightSeaGreen,
+    LightSkyBlue,
+    LightSlateGray,
+    LightSteelBlue,
+    LightYellow,
+    Lime,
+    LimeGreen,
+    Linen,
+    Magenta,
+    Maroon,
+    MediumAquamarine,
+    MediumBlue,
+    MediumOrchid,
+    MediumPurple,
+    MediumSeaGreen,
+    MediumSlateBlue,
+    MediumSpringGreen,
+    MediumTurquoise,
+    MediumVioletRed,
+    MidnightBlue,
+    MintCream,
+    MistyRose,
+    Moccasin,
+    NavajoWhite,
+    Navy,
+    OldLace,
+    Olive,
+    OliveDrab,
+    Orange,
+    OrangeRed,
+    Orchid,
+    PaleGoldenrod,
+    PaleGreen,
+    PaleTurquoise,
+    PaleVioletRed,
+    PapayaWhip,
+    PeachPuff,
+    Peru,
+    Pink,
+    Plum,
+    PowderBlue,
+    Purple,
+    RebeccaPurple,
+    Red,
+    RosyBrown,
+    RoyalBlue,
+    SaddleBrown,
+    Salmon,
+    SandyBrown,
+    SeaGreen,
+    SeaShell,
+    Sienna,
+    Silver,
+    SkyBlue,
+    SlateBlue,
+    SlateGray,
+    Snow,
+    SpringGreen,
+    SteelBlue,
+    Tan,
+    Teal,
+    Thistle,
+    Tomato,
+    Turquoise,
+    Violet,
+    Wheat,
+    White,
+    WhiteSmoke,
+    Yellow,
+    YellowGreen,
+}
+
+impl WebColor {
+    pub const ALL: [WebColor; 141] = {
+        use WebColor::*;
+        [
+            AliceBlue,
+            AntiqueWhite,
+            Aqua,
+            Aquamarine,
+            Azure,
+            Beige,
+            Bisque,
+            Black,
+            BlanchedAlmond,
+            Blue,
+            BlueViolet,
+            Brown,
+            BurlyWood,
+            CadetBlue,
+            Chartreuse,
+            Chocolate,
+            Coral,
+            CornflowerBlue,
+            Cornsilk,
+            Crimson,
+            Cyan,
+            DarkBlue,
+            DarkCyan,
+            DarkGoldenrod,
+            DarkGray,
+            DarkGreen,
+            DarkKhaki,
+            DarkMagenta,
+            DarkOliveGreen,
+            DarkOrange,
+            DarkOrchid,
+            DarkRed,
+            DarkSalmon,
+            DarkSeaGreen,
+            DarkSlateBlue,
+            DarkSlateGray,
+            DarkTurquoise,
+            DarkViolet,
+            DeepPink,
+            DeepSkyBlue,
+            DimGray,
+            DodgerBlue,
+            Firebrick,
+            FloralWhite,
+            ForestGreen,
+            Fuchsia,
+            Gainsboro,
+            GhostWhite,
+            Gold,
+            Goldenrod,
+            Gray,
+            Green,
+            GreenYellow,
+            Honeydew,
+            HotPink,
+            IndianRed,
+            Indigo,
+            Ivory,
+            Khaki,
+            Lavender,
+            LavenderBlush,
+            LawnGreen,
+            LemonChiffon,
+            LightBlue,
+            LightCoral,
+            LightCyan,
+            LightGoldenrodYellow,
+            LightGray,
+            LightGreen,
+            LightPink,
+            LightSalmon,
+            LightSeaGreen,
+            LightSkyBlue,
+            LightSlateGray,
+            LightSteelBlue,
+            LightYellow,
+            Lime,
+            LimeGreen,
+            Linen,
+            Magenta,
+            Maroon,
+            MediumAquamarine,
+            MediumBlue,
+            MediumOrchid,
+            MediumPurple,
+            MediumSeaGreen,
+            MediumSlateBlue,
+            MediumSpringGreen,
+            MediumTurquoise,
+            MediumVioletRed,
+            MidnightBlue,
+            MintCream,
+            MistyRose,
+            Moccasin,
+            NavajoWhite,
+            Navy,
+            OldLace,
+            Olive,
+            OliveDrab,
+            Orange,
+            OrangeRed,
+            Orchid,
+            PaleGoldenrod,
+            PaleGreen,
+            PaleTurquoise,
+            PaleVioletRed,
+            PapayaWhip,
+            PeachPuff,
+            Peru,
+            Pink,
+            Plum,
+            PowderBlue,
+            Purple,
+            RebeccaPurple,
+            Red,
+            RosyBrown,
+            RoyalBlue,
+            SaddleBrown,
+            Salmon,
+            SandyBrown,
+            SeaGreen,
+            SeaShell,
+            Sienna,
+            Silver,
+            SkyBlue,
+            SlateBlue,
+            SlateGray,
+            Snow,
+            SpringGreen,
+            SteelBlue,
+            Tan,
+            Teal,
+            Thistle,
+            Tomato,
+            Turquoise,
+            Violet,
+            Wheat,
+            White,
+            WhiteSmoke,
+            Yellow,
+            YellowGreen,
+        ]
+    };
+
+    /// Finds a color by its CSS name, ignoring case and accepting "grey" for "gray"
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase().replace("grey", "gray");
+        Self::ALL.into_iter().find(|color| format!("{color:?}").to_ascii_lowercase() == name)
+    }
+}
+
+impl From<WebColor> for Color {
+    fn from(color: WebColor) -> Self {
+        use WebColor::*;
+        match color {
+            AliceBlue => Color::rgb(0xF0, 0xF8, 0xFF),
+            AntiqueWhite => Color::rgb(0xFA, 0xEB, 0xD7),
+            Aqua => Color::rgb(0x00, 0xFF, 0xFF),
+            Aquamarine => Color::rgb(0x7F, 0xFF, 0xD4),
+            Azure => Color::rgb(0xF0, 0xFF, 0xFF),
+            Beige => Color::rgb(0xF5, 0xF5, 0xDC),
+            Bisque => Color::rgb(0xFF, 0xE4, 0xC4),
+            Black => Color::rgb(0x00, 0x00, 0x00),
+            BlanchedAlmond => Color::rgb(0xFF, 0xEB, 0xCD),
+            Blue => Color::rgb(0x00, 0x00, 0xFF),
+            BlueViolet => Color::rgb(0x8A, 0x2B, 0xE2),
+            Brown => Color::rgb(0xA5, 0x2A, 0x2A),
+            BurlyWood => Color::rgb(0xDE, 0xB8, 0x87),
+            CadetBlue => Color::rgb(0x5F, 0x9E, 0xA0),
+            Chartreuse => Color::rgb(0x7F, 0xFF, 0x00),
+            Chocolate => Color::rgb(0xD2, 0x69, 0x1E),
+            Coral => Color::rgb(0xFF, 0x7F, 0x50),
+            CornflowerBlue => Color::rgb(0x64, 0x95, 0xED),
+            Cornsilk => Color::rgb(0xFF, 0xF8, 0xDC),
+            Crimson => Color::rgb(0xDC, 0x14, 0x3C),
+            Cyan => Color::rgb(0x00, 0xFF, 0xFF),
+            DarkBlue => Color::rgb(0x00, 0x00, 0x8B),
+            DarkCyan => Color::rgb(0x00, 0x8B, 0x8B),
+            DarkGoldenrod => Color::rgb(0xB8, 0x86, 0x0B),
+            DarkGray => Color::rgb(0xA9, 0xA9, 0xA9),
+            DarkGreen => Color::rgb(0x00, 0x64, 0x00),
+            DarkKhaki => Color::rgb(0xBD, 0xB7, 0x6B),
+            DarkMagenta => Color::rgb(0x8B, 0x00, 0x8B),
+            DarkOliveGreen => Color::rgb(0x55, 0x6B, 0x2F),
+            DarkOrange => Color::rgb(0xFF, 0x8C, 0x00),
+            DarkOrchid => Color::rgb(0x99, 0x32, 0xCC),
+            DarkRed => Color::rgb(0x8B, 0x00, 0x00),
+            DarkSalmon => Color::rgb(0xE9, 0x96, 0x7A),
+            DarkSeaGreen => Color::rgb(0x8F, 0xBC, 0x8B),
+            DarkSlateBlue => Color::rgb(0x48, 0x3D, 0x8B),
+            DarkSlateGray => Color::rgb(0x2F, 0x4F, 0x4F),
+            DarkTurquoise => Color::rgb(0x00, 0xCE, 0xD1),
+            DarkViolet => Color::rgb(0x94, 0x00, 0xD3),
+            DeepPink => Color::rgb(0xFF, 0x14, 0x93),
+            DeepSkyBlue => Color::rgb(0x00, 0xBF, 0xFF),
+            DimGray => Color::rgb(0x69, 0x69, 0x69),
+            DodgerBlue => Color::rgb(0x1E, 0x90, 0xFF),
+            Firebrick => Color::rgb(0xB2, 0x22, 0x22),
+            FloralWhite => Color::rgb(0xFF, 0xFA, 0xF0),
+            ForestGreen => Color::rgb(0x22, 0x8B, 0x22),
+            Fuchsia => Color::rgb(0xFF, 0x00, 0xFF),
+            Gainsboro => Color::rgb(0xDC, 0xDC, 0xDC),
+            GhostWhite => Color::rgb(0xF8, 0xF8, 0xFF),
+            Gold => Color::rgb(0xFF, 0xD7, 0x00),
+            Goldenrod => Color::rgb(0xDA, 0xA5, 0x20),
+            Gray => Color::rgb(0x80, 0x80, 0x80),
+            Green => Color::rgb(0x00, 0x80, 0x00),
+            GreenYellow => Color::rgb(0xAD, 0xFF, 0x2F),
+            Honeydew => Color::rgb(0xF0, 0xFF, 0xF0),
+            HotPink => Color::rgb(0xFF, 0x69, 0xB4),
+            IndianRed => Color::rgb(0xCD, 0x5C, 0x5C),
+            Indigo => Color::rgb(0x4B, 0x00, 0x82),
+            Ivory => Color::rgb(0xFF, 0xFF, 0xF0),
+            Khaki => Color::rgb(0xF0, 0xE6, 0x8C),
+            Lavender => Color::rgb(0xE6, 0xE6, 0xFA),
+            LavenderBlush => Color::rgb(0xFF, 0xF0, 0xF5),
+            LawnGreen => Color::rgb(0x7C, 0xFC, 0x00),
+            LemonChiffon => Color::rgb(0xFF, 0xFA, 0xCD),
+            LightBlue => Color::rgb(0xAD, 0xD8, 0xE6),
+            LightCoral => Color::rgb(0xF0, 0x80, 0x80),
+            LightCyan => Color::rgb(0xE0, 0xFF, 0xFF),
+            LightGoldenrodYellow => Color::rgb(0xFA, 0xFA, 0xD2),
+            LightGray => Color::rgb(0xD3, 0xD3, 0xD3),
+            LightGreen => Color::rgb(0x90, 0xEE, 0x90),
+            LightPink => Color::rgb(0xFF, 0xB6, 0xC1),
+            LightSalmon => Color::rgb(0xFF, 0xA0, 0x7A),
+            LightSeaGreen => Color::rgb(0x20, 0xB2, 0xAA),
+            LightSkyBlue => Color::rgb(0x87, 0xCE, 0xFA),
+            LightSlateGray => Color::rgb(0x77, 0x88, 0x99),
+            LightSteelBlue => Color::rgb(0xB0, 0xC4, 0xDE),
+            LightYellow => Color::rgb(0xFF, 0xFF, 0xE0),
+            Lime => Color::rgb(0x00, 0xFF, 0x00),
+            LimeGreen => Color::rgb(0x32, 0xCD, 0x32),
+            Linen => Color::rgb(0xFA, 0xF0, 0xE6),
+            Magenta => Color::rgb(0xFF, 0x00, 0xFF),
+            Maroon => Color::rgb(0x80, 0x00, 0x00),
+            MediumAquamarine => Color::rgb(0x66, 0xCD, 0xAA),
+            MediumBlue => Color::rgb(0x00, 0x00, 0xCD),
+            MediumOrchid => Color::rgb(0xBA, 0x55, 0xD3),
+            MediumPurple => Color::rgb(0x93, 0x70, 0xDB),
+            MediumSeaGreen => Color::rgb(0x3C, 0xB3, 0x71),
+            MediumSlateBlue => Color::rgb(0x7B, 0x68, 0xEE),
+            MediumSpringGreen => Color::rgb(0x00, 0xFA, 0x9A),
+            MediumTurquoise => Color::rgb(0x48, 0xD1, 0xCC),
+            MediumVioletRed => Color::rgb(0xC7, 0x15, 0x85),
+            MidnightBlue => Color::rgb(0x19, 0x19, 0x70),
+            MintCream => Color::rgb(0xF5, 0xFF, 0xFA),
+            MistyRose => Color::rgb(0xFF, 0xE4, 0xE1),
+            Moccasin => Color::rgb(0xFF, 0xE4, 0xB5),
+            NavajoWhite => Color::rgb(0xFF, 0xDE, 0xAD),
+            Navy => Color::rgb(0x00, 0x00, 0x80),
+            OldLace => Color::rgb(0xFD, 0xF5, 0xE6),
+            Olive => Color::rgb(0x80, 0x80, 0x00),
+            OliveDrab => Color::rgb(0x6B, 0x8E, 0x23),
+            Orange => Color::rgb(0xFF, 0xA5, 0x00),
+            OrangeRed => Color::rgb(0xFF, 0x45, 0x00),
+            Orchid => Color::rgb(0xDA, 0x70, 0xD6),
+            PaleGoldenrod => Color::rgb(0xEE, 0xE8, 0xAA),
+            PaleGreen => Color::rgb(0x98, 0xFB, 0x98),
+            PaleTurquoise => Color::rgb(0xAF, 0xEE, 0xEE),
+            PaleVioletRed => Color::rgb(0xDB, 0x70, 0x93),
+            PapayaWhip => Color::rgb(0xFF, 0xEF, 0xD5),
+            PeachPuff => Color::rgb(0xFF, 0xDA, 0xB9),
+            Peru => Color::rgb(0xCD, 0x85, 0x3F),
+            Pink => Color::rgb(0xFF, 0xC0, 0xCB),
+            Plum => Color::rgb(0xDD, 0xA0, 0xDD),
+            PowderBlue => Color::rgb(0xB0, 0xE0, 0xE6),
+            Purple => Color::rgb(0x80, 0x00, 0x80),
+            RebeccaPurple => Color::rgb(0x66, 0x33, 0x99),
+            Red => Color::rgb(0xFF, 0x00, 0x00),
+            RosyBrown => Color::rgb(0xBC, 0x8F, 0x8F),
+            RoyalBlue => Color::rgb(0x41, 0x69, 0xE1),
+            SaddleBrown => Color::rgb(0x8B, 0x45, 0x13),
+            Salmon => Color::rgb(0xFA, 0x80, 0x72),
+            SandyBrown => Color::rgb(0xF4, 0xA4, 0x60),
+            SeaGreen => Color::rgb(0x2E, 0x8B, 0x57),
+            SeaShell => Color::rgb(0xFF, 0xF5, 0xEE),
+            Sienna => Color::rgb(0xA0, 0x52, 0x2D),
+            Silver => Color::rgb(0xC0, 0xC0, 0xC0),
+            SkyBlue => Color::rgb(0x87, 0xCE, 0xEB),
+            SlateBlue => Color::rgb(0x6A, 0x5A, 0xCD),
+            SlateGray => Color::rgb(0x70, 0x80, 0x90),
+            Snow => Color::rgb(0xFF, 0xFA, 0xFA),
+            SpringGreen => Color::rgb(0x00, 0xFF, 0x7F),
+            SteelBlue => Color::rgb(0x46, 0x82, 0xB4),
+            Tan => Color::rgb(0xD2, 0xB4, 0x8C),
+            Teal => Color::rgb(0x00, 0x80, 0x80),
+            Thistle => Color::rgb(0xD8, 0xBF, 0xD8),
+            Tomato => Color::rgb(0xFF, 0x63, 0x47),
+            Turquoise => Color::rgb(0x40, 0xE0, 0xD0),
+            Violet => Color::rgb(0xEE, 0x82, 0xEE),
+            Wheat => Color::rgb(0xF5, 0xDE, 0xB3),
+            White => Color::rgb(0xFF, 0xFF, 0xFF),
+            WhiteSmoke => Color::rgb(0xF5, 0xF5, 0xF5),
+            Yellow => Color::rgb(0xFF, 0xFF, 0x00),
+            YellowGreen => Color::rgb(0x9A, 0xCD, 0x32),
+        }
+    }
+}
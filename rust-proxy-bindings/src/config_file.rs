@@ -0,0 +1,233 @@
+use std::{env, ffi::{CString, c_char}, fs, io, path::Path};
+
+use serde::Deserialize;
+
+use crate::{Config, RequestedPlatform, RequestedRenderer, UnsignedBricks, paths};
+
+const CONFIG_NAME: &str = "config.toml";
+/// The game's command line argument that picks a mod profile, as `--bluebrick-profile NAME` or `--bluebrick-profile=NAME`
+const PROFILE_ARG: &str = "--bluebrick-profile";
+
+/// The optional settings in `config.toml` in the root, anything left out keeps the proxy's value.
+/// Relative folders are relative to the root.
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct FileConfig {
+    platform: Option<String>,
+    renderer: Option<String>,
+    overlay: Option<bool>,
+    safe_mode: Option<bool>,
+    unsigned_bricks: Option<String>,
+    profile: Option<String>,
+    log_level: Option<String>,
+    log_sinks: Option<String>,
+    log_server: Option<String>,
+    log_retention: Option<u32>,
+    log_max_size: Option<u64>,
+    brick_dir: Option<String>,
+    data_dir: Option<String>,
+    cache_dir: Option<String>,
+    log_dir: Option<String>,
+}
+
+impl FileConfig {
+    fn read(root: &Path, problems: &mut Vec<String>) -> Self {
+        let path = root.join(CONFIG_NAME);
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Self::default(),
+            Err(e) => {
+                problems.push(format!("Could not read {}, using the defaults: {e}", path.display()));
+                return Self::default();
+            }
+        };
+
+        match toml::from_str(&text) {
+            Ok(config) => config,
+            Err(e) => {
+                problems.push(format!("Could not parse {}, using the defaults: {}", path.display(), e.message()));
+                Self::default()
+            }
+        }
+    }
+
+    /// Replaces settings with the ones set in the environment
+    fn apply_env(&mut self, problems: &mut Vec<String>) {
+        let var = |key: &str| env::var(key).ok().filter(|value| !value.trim().is_empty());
+
+        if let Some(platform) = var("BLUEBRICK_PLATFORM") {
+            self.platform = Some(platform);
+        }
+        if let Some(renderer) = var("BLUEBRICK_RENDERER") {
+            self.renderer = Some(renderer);
+        }
+        let mut switch = |key: &str, setting: &mut Option<bool>| {
+            if let Some(value) = var(key) {
+                match value.trim().to_ascii_lowercase().as_str() {
+                    "1" | "true" | "on" | "yes" => *setting = Some(true),
+                    "0" | "false" | "off" | "no" => *setting = Some(false),
+                    _ => problems.push(format!("{key}={value} is not on or off, ignoring it")),
+                }
+            }
+        };
+        switch("BLUEBRICK_OVERLAY", &mut self.overlay);
+        switch("BLUEBRICK_SAFE_MODE", &mut self.safe_mode);
+        if let Some(unsigned_bricks) = var("BLUEBRICK_UNSIGNED_BRICKS") {
+            self.unsigned_bricks = Some(unsigned_bricks);
+        }
+        if let Some(profile) = var("BLUEBRICK_PROFILE") {
+            self.profile = Some(profile);
+        }
+        if let Some(log_level) = var("BLUEBRICK_LOG_LEVEL") {
+            self.log_level = Some(log_level);
+        }
+        if let Some(log_sinks) = var("BLUEBRICK_LOG_SINKS") {
+            self.log_sinks = Some(log_sinks);
+        }
+        if let Some(log_server) = var("BLUEBRICK_LOG_SERVER") {
+            self.log_server = Some(log_server);
+        }
+        let mut number = |key: &str| var(key).and_then(|value| match value.trim().parse::<u64>() {
+            Ok(number) => Some(number),
+            Err(_) => {
+                problems.push(format!("{key}={value} is not a whole number, ignoring it"));
+                None
+            }
+        });
+        if let Some(log_retention) = number("BLUEBRICK_LOG_RETENTION") {
+            self.log_retention = Some(u32::try_from(log_retention).unwrap_or(u32::MAX));
+        }
+        if let Some(log_max_size) = number("BLUEBRICK_LOG_MAX_SIZE") {
+            self.log_max_size = Some(log_max_size);
+        }
+        if let Some(brick_dir) = var("BLUEBRICK_BRICK_DIR") {
+            self.brick_dir = Some(brick_dir);
+        }
+        if let Some(data_dir) = var("BLUEBRICK_DATA_DIR") {
+            self.data_dir = Some(data_dir);
+        }
+        if let Some(cache_dir) = var("BLUEBRICK_CACHE_DIR") {
+            self.cache_dir = Some(cache_dir);
+        }
+        if let Some(log_dir) = var("BLUEBRICK_LOG_DIR") {
+            self.log_dir = Some(log_dir);
+        }
+    }
+
+    /// Replaces settings with the ones passed on the game's command line, which the game itself ignores
+    fn apply_args(&mut self, problems: &mut Vec<String>) {
+        let mut args = env::args_os().skip(1).map(|arg| arg.to_string_lossy().to_string());
+        while let Some(arg) = args.next() {
+            if arg == PROFILE_ARG {
+                match args.next() {
+                    Some(profile) => self.profile = Some(profile),
+                    None => problems.push(format!("{PROFILE_ARG} needs a profile name, ignoring it")),
+                }
+            } else if let Some(profile) = arg.strip_prefix(PROFILE_ARG).and_then(|rest| rest.strip_prefix('=')) {
+                self.profile = Some(String::from(profile));
+            }
+        }
+    }
+}
+
+/// Leaks the string so it can be handed to the loader for the rest of the game
+fn leak(string: &str) -> *const c_char {
+    let string = CString::new(string.replace('\0', "")).unwrap_or_default();
+    string.into_raw()
+}
+
+/// Checks a folder setting and makes it absolute, returns None if it points at a file
+fn folder(root: &Path, name: &str, folder: &str, problems: &mut Vec<String>) -> Option<*const c_char> {
+    let path = root.join(folder);
+    if path.exists() && !path.is_dir() {
+        problems.push(format!("The {name} folder {folder:?} is not a folder, using the default"));
+        return None;
+    }
+    Some(leak(&path.to_string_lossy()))
+}
+
+/// Overrides the proxy's config with `config.toml` in the root, then the environment and then the game's command line,
+/// keeping the proxy's values for anything that is missing or invalid
+pub(crate) fn apply(mut config: Config, root: &Path) -> Config {
+    let mut problems = Vec::new();
+    let mut file = FileConfig::read(root, &mut problems);
+    file.apply_env(&mut problems);
+    file.apply_args(&mut problems);
+
+    if let Some(platform) = file.platform {
+        match RequestedPlatform::from_name(&platform) {
+            Some(platform) => config.platform = platform,
+            None => problems.push(format!("Unknown platform {platform:?}, using {:?}", config.platform)),
+        }
+    }
+
+    if let Some(renderer) = file.renderer {
+        match RequestedRenderer::from_name(&renderer) {
+            Some(renderer) => config.renderer = renderer,
+            None => problems.push(format!("Unknown renderer {renderer:?}, using {:?}", config.renderer)),
+        }
+    }
+
+    if let Some(overlay) = file.overlay {
+        config.overlay_enabled = overlay;
+    }
+
+    if let Some(safe_mode) = file.safe_mode {
+        config.safe_mode = safe_mode;
+    }
+
+    if let Some(unsigned_bricks) = file.unsigned_bricks {
+        match UnsignedBricks::from_name(&unsigned_bricks) {
+            Some(unsigned_bricks) => config.unsigned_bricks = unsigned_bricks,
+            None => problems.push(format!("Unknown unsigned_bricks policy {unsigned_bricks:?}, use allow, warn or block. Using {:?}", config.unsigned_bricks)),
+        }
+    }
+
+    if let Some(profile) = file.profile.filter(|profile| !profile.trim().is_empty()) {
+        config.profile = leak(profile.trim());
+    }
+
+    if let Some(log_level) = file.log_level {
+        config.log_level = leak(&log_level);
+    }
+
+    if let Some(log_sinks) = file.log_sinks {
+        config.log_sinks = leak(&log_sinks);
+    }
+
+    if let Some(log_server) = file.log_server.filter(|log_server| !log_server.trim().is_empty()) {
+        config.log_server = leak(log_server.trim());
+    }
+
+    match file.log_retention {
+        Some(0) => problems.push(String::from("log_retention has to keep at least 1 session, using the default")),
+        Some(log_retention) => config.log_retention = log_retention,
+        None => {}
+    }
+
+    match file.log_max_size {
+        Some(0) => problems.push(String::from("log_max_size has to be at least 1 byte, using the default")),
+        Some(log_max_size) => config.log_max_size = log_max_size,
+        None => {}
+    }
+
+    if let Some(root) = paths::root_override() {
+        config.root_dir = leak(&root.to_string_lossy());
+    }
+    for (name, value, field) in [
+        ("brick", file.brick_dir, &mut config.brick_dir),
+        ("data", file.data_dir, &mut config.data_dir),
+        ("cache", file.cache_dir, &mut config.cache_dir),
+        ("log", file.log_dir, &mut config.log_dir),
+    ] {
+        if let Some(path) = value.and_then(|value| folder(root, name, &value, &mut problems)) {
+            *field = path;
+        }
+    }
+
+    if !problems.is_empty() {
+        config.problems = leak(&problems.join("\n"));
+    }
+
+    config
+}
@@ -1,40 +1,303 @@
-use dlopen::wrapper::{Container, WrapperApi};
+mod config_file;
+mod paths;
+
+use std::{env, ffi::{CStr, c_char, c_int}, mem::{self, MaybeUninit}, path::{Path, PathBuf}, ptr};
+
+use dlopen::wrapper::{OptionalContainer, WrapperApi};
 use dlopen_derive::WrapperApi;
 
 #[repr(C)]
-#[derive(PartialEq, Clone, Copy)]
+#[derive(PartialEq, Clone, Copy, Debug)]
 pub enum RequestedRenderer {
     DX9,
+    /// Hooks every supported renderer and uses whichever the game presents with
+    Auto,
+}
+
+impl RequestedRenderer {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "dx9" | "d3d9" | "directx9" => Some(Self::DX9),
+            "auto" => Some(Self::Auto),
+            _ => None,
+        }
+    }
 }
 
 #[repr(C)]
-#[derive(PartialEq, Clone, Copy)]
+#[derive(PartialEq, Clone, Copy, Debug)]
 pub enum RequestedPlatform {
     Win32,
+    /// Meant to use the platform that owns the game's window, for now it is always Win32
+    Auto,
+}
+
+impl RequestedPlatform {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "win32" | "windows" => Some(Self::Win32),
+            "auto" => Some(Self::Auto),
+            _ => None,
+        }
+    }
+}
+
+/// What the loader does with bricks that aren't signed by a trusted key.
+/// Bricks whose signature doesn't match are never loaded.
+#[repr(C)]
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum UnsignedBricks {
+    Allow,
+    /// Loads them with a warning
+    Warn,
+    Block,
+}
+
+impl UnsignedBricks {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "allow" => Some(Self::Allow),
+            "warn" => Some(Self::Warn),
+            "block" => Some(Self::Block),
+            _ => None,
+        }
+    }
 }
 
+/// What the loader should start with. Proxies build it with [`Config::new`],
+/// then `config.toml` in the BlueBrick folder and the environment can override it in [`load_bluebrick`].
+/// The loader is handed a pointer to it and reads `size` first, so proxies and loaders of different versions can be mixed.
 #[derive(Clone, Copy)]
 #[repr(C)]
 pub struct Config {
+    /// The size of the struct in bytes as the proxy was built with it, fields are only ever added to the end
+    pub size: u32,
     pub platform: RequestedPlatform,
-    pub renderer: RequestedRenderer
+    pub renderer: RequestedRenderer,
+    pub overlay_enabled: bool,
+    /// Minimum log levels like `info,SomeMod=trace,mod:OtherMod=warning`, null for the loader's default
+    pub log_level: *const c_char,
+    /// The folder holding the `libraries` and `mods` folders, null for the root
+    pub brick_dir: *const c_char,
+    /// Problems found while reading the config, one per line, null if there were none
+    pub problems: *const c_char,
+    /// The folder holding the loader and `config.toml`, null for the folder the loader is in.
+    /// Set by the proxy when `BLUEBRICK_ROOT` overrides it.
+    pub root_dir: *const c_char,
+    /// Where bricks keep their files, null for `data` in the root
+    pub data_dir: *const c_char,
+    /// Files that can be deleted at any time, null for `cache` in the root
+    pub cache_dir: *const c_char,
+    /// Logs and crash reports, null for `logs` in the root
+    pub log_dir: *const c_char,
+    /// Starts with every brick disabled and the diagnostics window open
+    pub safe_mode: bool,
+    pub unsigned_bricks: UnsignedBricks,
+    /// The mod profile to start with, null for the one that was used last
+    pub profile: *const c_char,
+    /// Where logs go, a comma separated list like `console,file,server`, null for the console and the log files
+    pub log_sinks: *const c_char,
+    /// The address the log server listens on, `host:port` or `unix:/path/to/socket`, null for the default
+    pub log_server: *const c_char,
+    /// How many sessions of log files are kept, 0 for the default
+    pub log_retention: u32,
+    /// The size in bytes a log file grows to before the session continues in a new part, 0 for the default
+    pub log_max_size: u64,
+}
+
+// the strings are leaked when the config is read and never change after
+unsafe impl Send for Config {}
+unsafe impl Sync for Config {}
+
+impl Config {
+    pub const fn new(platform: RequestedPlatform, renderer: RequestedRenderer) -> Self {
+        Self {
+            size: mem::size_of::<Self>() as u32,
+            platform,
+            renderer,
+            overlay_enabled: true,
+            log_level: ptr::null(),
+            brick_dir: ptr::null(),
+            problems: ptr::null(),
+            root_dir: ptr::null(),
+            data_dir: ptr::null(),
+            cache_dir: ptr::null(),
+            log_dir: ptr::null(),
+            safe_mode: false,
+            unsigned_bricks: UnsignedBricks::Allow,
+            profile: ptr::null(),
+            log_sinks: ptr::null(),
+            log_server: ptr::null(),
+            log_retention: 0,
+            log_max_size: 0,
+        }
+    }
+
+    /// Reads the config a proxy handed the loader. Fields an older proxy doesn't have keep their defaults
+    /// and fields from a newer proxy that this version doesn't know are ignored.
+    ///
+    /// # Safety
+    /// `config` has to be null or point to as many bytes as its `size` says.
+    pub unsafe fn read(config: *const Config) -> Result<Self, String> {
+        if config.is_null() {
+            return Err(String::from("the proxy didn't pass a config"));
+        }
+
+        let size = unsafe { config.cast::<u32>().read_unaligned() } as usize;
+        let required = mem::offset_of!(Config, renderer) + mem::size_of::<RequestedRenderer>();
+        if size < required {
+            return Err(format!("the proxy's config is {size} bytes, which is too small to hold one"));
+        }
+
+        let mut read = MaybeUninit::new(Self::new(RequestedPlatform::Win32, RequestedRenderer::DX9));
+        unsafe { ptr::copy_nonoverlapping(config.cast::<u8>(), read.as_mut_ptr().cast::<u8>(), size.min(mem::size_of::<Self>())) };
+
+        // a newer proxy can send values this version doesn't have, which can't be read as the enums
+        let bytes = read.as_ptr().cast::<u8>();
+        let int_at = |offset: usize| unsafe { bytes.add(offset).cast::<c_int>().read() };
+        let bool_at = |offset: usize| unsafe { bytes.add(offset).read() };
+        let checks = [
+            ("platform", (0..=RequestedPlatform::Auto as c_int).contains(&int_at(mem::offset_of!(Config, platform)))),
+            ("renderer", (0..=RequestedRenderer::Auto as c_int).contains(&int_at(mem::offset_of!(Config, renderer)))),
+            ("overlay_enabled", bool_at(mem::offset_of!(Config, overlay_enabled)) <= 1),
+            ("safe_mode", bool_at(mem::offset_of!(Config, safe_mode)) <= 1),
+            ("unsigned_bricks", (0..=UnsignedBricks::Block as c_int).contains(&int_at(mem::offset_of!(Config, unsigned_bricks)))),
+        ];
+        if let Some((name, _)) = checks.iter().find(|(_, valid)| !valid) {
+            return Err(format!("the proxy's config has a {name} this version of BlueBrick doesn't know"));
+        }
+
+        let mut config = unsafe { read.assume_init() };
+        config.size = mem::size_of::<Self>() as u32;
+        Ok(config)
+    }
+
+    fn read_string(string: *const c_char) -> Option<String> {
+        if string.is_null() {
+            return None;
+        }
+        let string = unsafe { CStr::from_ptr(string) };
+        Some(String::from_utf8_lossy(string.to_bytes()).to_string())
+    }
+
+    pub fn log_level(&self) -> Option<String> {
+        Self::read_string(self.log_level)
+    }
+
+    pub fn brick_dir(&self) -> Option<PathBuf> {
+        Self::read_string(self.brick_dir).map(PathBuf::from)
+    }
+
+    pub fn root_dir(&self) -> Option<PathBuf> {
+        Self::read_string(self.root_dir).map(PathBuf::from)
+    }
+
+    pub fn data_dir(&self) -> Option<PathBuf> {
+        Self::read_string(self.data_dir).map(PathBuf::from)
+    }
+
+    pub fn cache_dir(&self) -> Option<PathBuf> {
+        Self::read_string(self.cache_dir).map(PathBuf::from)
+    }
+
+    pub fn log_dir(&self) -> Option<PathBuf> {
+        Self::read_string(self.log_dir).map(PathBuf::from)
+    }
+
+    pub fn profile(&self) -> Option<String> {
+        Self::read_string(self.profile)
+    }
+
+    pub fn log_sinks(&self) -> Option<String> {
+        Self::read_string(self.log_sinks)
+    }
+
+    pub fn log_server(&self) -> Option<String> {
+        Self::read_string(self.log_server)
+    }
+
+    pub fn log_retention(&self) -> Option<u32> {
+        Some(self.log_retention).filter(|&retention| retention > 0)
+    }
+
+    pub fn log_max_size(&self) -> Option<u64> {
+        Some(self.log_max_size).filter(|&max_size| max_size > 0)
+    }
+
+    pub fn problems(&self) -> Vec<String> {
+        Self::read_string(self.problems).map(|problems| problems.lines().map(String::from).collect()).unwrap_or_default()
+    }
+}
+
+/// The config proxies passed by value to `start_bluebrick` before [`Config`] had a size, only the platform and renderer.
+/// The fields are plain ints so values the enums don't have can be turned away instead of read.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct LegacyConfig {
+    pub platform: c_int,
+    pub renderer: c_int,
+}
+
+impl LegacyConfig {
+    /// Loaders from before `start_bluebrick_sized` only know Win32 and DX9, so that is all they are ever sent
+    pub const OLDEST: Self = Self { platform: RequestedPlatform::Win32 as c_int, renderer: RequestedRenderer::DX9 as c_int };
+
+    /// A [`Config`] with the platform and renderer, and everything else at its default
+    pub fn read(self) -> Result<Config, String> {
+        let platform = [RequestedPlatform::Win32, RequestedPlatform::Auto].into_iter().find(|&platform| platform as c_int == self.platform);
+        let renderer = [RequestedRenderer::DX9, RequestedRenderer::Auto].into_iter().find(|&renderer| renderer as c_int == self.renderer);
+        match (platform, renderer) {
+            (Some(platform), Some(renderer)) => Ok(Config::new(platform, renderer)),
+            _ => Err(format!("the proxy asked for platform {} and renderer {}, which this version of BlueBrick doesn't know", self.platform, self.renderer)),
+        }
+    }
+}
+
+/// What every loader exports
+#[derive(WrapperApi)]
+struct LegacyBBApi {
+    start_bluebrick: extern "C" fn(config: LegacyConfig),
 }
 
+/// What loaders that take the whole config export
 #[derive(WrapperApi)]
 struct BBApi {
-    start_bluebrick: extern "C" fn(config: Config),
+    start_bluebrick_sized: extern "C" fn(config: *const Config),
+}
+
+/// Applies `config.toml` and the environment like [`load_bluebrick`] does, for loaders started some other way.
+/// `default_root` is where the config is looked for unless `BLUEBRICK_ROOT` is set.
+pub fn read_config(config: Config, default_root: &Path) -> Config {
+    let root = paths::root_override().unwrap_or_else(|| default_root.to_path_buf());
+    config_file::apply(config, &root)
+}
+
+/// Shows an error in a message box where there is one, and falls back to stderr for headless games
+pub fn show_error(title: &str, message: &str) {
+    #[cfg(windows)]
+    if msgbox::create(title, message, msgbox::IconType::Error).is_ok() {
+        return;
+    }
+    eprintln!("{title}: {message}");
 }
 
 pub fn load_bluebrick(config: Config) {
-    let bluebrick = match unsafe { Container::<BBApi>::load("bluebrick/bluebrick") } {
+    let root = paths::root();
+    let config = config_file::apply(config, &root);
+
+    let loader = root.join(format!("{}bluebrick{}", env::consts::DLL_PREFIX, env::consts::DLL_SUFFIX));
+    let bluebrick = match unsafe { OptionalContainer::<LegacyBBApi, BBApi>::load(&loader) } {
         Ok(bb) => bb,
         Err(e) => {
-            let _ = msgbox::create("Error Loading BlueBrick", &format!("Problem opening loader:\n{e:?}"), msgbox::IconType::Error);
+            show_error("Error Loading BlueBrick", &format!("Problem opening loader {}:\n{e:?}", loader.display()));
             return;
         }
     };
 
-    bluebrick.start_bluebrick(config);
+    match bluebrick.optional() {
+        Some(sized) => sized.start_bluebrick_sized(&config),
+        None => bluebrick.start_bluebrick(LegacyConfig::OLDEST),
+    }
 
     std::mem::forget(bluebrick); // keeps from dropping, which would unload bluebrick
 }
@@ -0,0 +1,48 @@
+use std::{env, path::PathBuf};
+
+/// The folder the proxy was loaded from, which is the game's folder for a normal install
+#[cfg(windows)]
+fn proxy_dir() -> Option<PathBuf> {
+    use std::ffi::c_void;
+
+    const GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS: u32 = 0x4;
+    const GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT: u32 = 0x2;
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn GetModuleHandleExW(flags: u32, module_name: *const u16, module: *mut *mut c_void) -> i32;
+        fn GetModuleFileNameW(module: *mut c_void, file_name: *mut u16, size: u32) -> u32;
+    }
+
+    let mut module = std::ptr::null_mut();
+    let flags = GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS | GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT;
+    if unsafe { GetModuleHandleExW(flags, proxy_dir as *const u16, &mut module) } == 0 {
+        return None;
+    }
+
+    let mut name = [0u16; 1024];
+    let len = unsafe { GetModuleFileNameW(module, name.as_mut_ptr(), name.len() as u32) } as usize;
+    if len == 0 {
+        return None;
+    }
+    PathBuf::from(String::from_utf16_lossy(&name[..len])).parent().map(PathBuf::from)
+}
+
+/// The folder the proxy was loaded from, which is the game's folder for a normal install
+#[cfg(not(windows))]
+fn proxy_dir() -> Option<PathBuf> {
+    let info = dlopen::raw::AddressInfoObtainer::new().obtain(proxy_dir as *const ()).ok()?;
+    PathBuf::from(info.dll_path).parent().map(PathBuf::from)
+}
+
+/// The folder holding the loader and `config.toml`: `BLUEBRICK_ROOT` if it is set, otherwise `bluebrick` next to the proxy.
+/// The working directory is only used if the proxy can't be found, since launchers often change it.
+pub(crate) fn root() -> PathBuf {
+    root_override().unwrap_or_else(|| proxy_dir().unwrap_or_default().join("bluebrick"))
+}
+
+/// `BLUEBRICK_ROOT` made absolute, which the loader is told about since it can't see where it was loaded from otherwise
+pub(crate) fn root_override() -> Option<PathBuf> {
+    let root = PathBuf::from(env::var_os("BLUEBRICK_ROOT").filter(|root| !root.is_empty())?);
+    Some(env::current_dir().map(|dir| dir.join(&root)).unwrap_or(root))
+}
@@ -0,0 +1,169 @@
+#[cfg(unix)]
+mod unix;
+#[cfg(windows)]
+mod windows;
+
+#[cfg(unix)]
+use unix as platform;
+#[cfg(windows)]
+use windows as platform;
+
+use std::{backtrace::Backtrace, env, fmt::Write as _, fs, io, panic, path::PathBuf, sync::{Mutex, MutexGuard, TryLockError, atomic::{AtomicBool, Ordering}}, thread};
+
+use chrono::Local;
+
+use bluebrick::logger::Severity;
+
+use crate::logger::MainLogger;
+use crate::paths;
+
+pub(crate) use platform::module_at;
+
+const MARKER_NAME: &str = "last_crash";
+const LOG_LINES: u64 = 200;
+
+struct ActiveHook {
+    name: &'static str,
+    target: usize,
+}
+
+static HOOKS: Mutex<Vec<ActiveHook>> = Mutex::new(Vec::new());
+static BRICKS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+/// The most recent panic, included in the report if the process goes down after it
+static LAST_PANIC: Mutex<Option<String>> = Mutex::new(None);
+/// Set once a report is written, the process is going down so there is never a second one.
+static REPORTED: AtomicBool = AtomicBool::new(false);
+
+/// Installs the panic hook and the platform's fatal error handler
+pub(crate) fn install() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload.downcast_ref::<&str>().map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| String::from("Box<dyn Any>"));
+        let location = info.location().map(|location| format!(" at {location}")).unwrap_or_default();
+        let cause = format!("panic on thread {}{location}: {message}", thread::current().name().unwrap_or("unnamed"));
+
+        // most panics are caught or only end their thread, so they are logged and only reported if the process goes down
+        if cfg!(panic = "abort") {
+            report(&cause, None);
+        } else {
+            if let Some(mut logger) = MainLogger::try_instance() {
+                logger.log_with_severity(&cause, Severity::Error);
+            }
+            if let Some(mut last_panic) = try_lock(&LAST_PANIC) {
+                *last_panic = Some(cause);
+            }
+        }
+        previous(info);
+    }));
+
+    platform::install();
+}
+
+/// Records a hook once it is enabled, so crash reports can list what was patched
+#[cfg_attr(not(windows), allow(unused))]
+pub(crate) fn add_hook(name: &'static str, target: usize) {
+    if let Some(mut hooks) = try_lock(&HOOKS) {
+        hooks.push(ActiveHook { name, target });
+    }
+}
+
+pub(crate) fn set_bricks(bricks: Vec<String>) {
+    if let Some(mut list) = try_lock(&BRICKS) {
+        *list = bricks;
+    }
+}
+
+/// Returns the report left by a crash in the previous session, removing the marker so it is only shown once
+pub(crate) fn take_marker() -> Option<PathBuf> {
+    let marker = paths::crash_dir().join(MARKER_NAME);
+    let report = fs::read_to_string(&marker).ok()?;
+    let _ = fs::remove_file(&marker);
+    Some(PathBuf::from(report.trim()))
+}
+
+/// Never waits on a lock, the thread that holds it may be the one that crashed
+fn try_lock<T>(mutex: &Mutex<T>) -> Option<MutexGuard<'_, T>> {
+    match mutex.try_lock() {
+        Ok(guard) => Some(guard),
+        Err(TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+        Err(TryLockError::WouldBlock) => None,
+    }
+}
+
+fn describe_address(address: usize) -> String {
+    match platform::module_at(address) {
+        Some((module, offset)) => format!("{address:#X} ({}+{offset:#X})", module.display()),
+        None => format!("{address:#X} (unknown module)"),
+    }
+}
+
+/// Writes a report and leaves the marker for the next launch, only called when the process is going down.
+/// This allocates and may take locks, which isn't safe in a signal handler, but the process is already going down.
+fn report(cause: &str, address: Option<usize>) {
+    if REPORTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    match write_report(cause, address) {
+        Ok(path) => {
+            let _ = fs::write(paths::crash_dir().join(MARKER_NAME), path.to_string_lossy().as_bytes());
+            eprintln!("BlueBrick wrote a crash report to {}", path.display());
+        }
+        Err(e) => eprintln!("BlueBrick could not write a crash report: {e}"),
+    }
+}
+
+fn write_report(cause: &str, address: Option<usize>) -> io::Result<PathBuf> {
+    let now = Local::now();
+    let executable = env::current_exe().map(|path| path.display().to_string()).unwrap_or_else(|e| format!("unknown ({e})"));
+
+    let mut report = String::new();
+    let _ = writeln!(report, "==== BlueBrick crash report ====");
+    let _ = writeln!(report, "Loader version: {}", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(report, "Game executable: {executable}");
+    let _ = writeln!(report, "Time: {}", now.format("%F %T"));
+    let _ = writeln!(report, "Thread: {}", thread::current().name().unwrap_or("unnamed"));
+    let _ = writeln!(report, "Cause: {cause}");
+    if let Some(address) = address {
+        let _ = writeln!(report, "Faulting address: {}", describe_address(address));
+    }
+    if let Some(Some(panic)) = try_lock(&LAST_PANIC).as_deref() {
+        let _ = writeln!(report, "Last panic: {panic}");
+    }
+
+    let _ = writeln!(report, "\nBacktrace:\n{}", Backtrace::force_capture());
+
+    let _ = writeln!(report, "Bricks:");
+    match try_lock(&BRICKS) {
+        Some(bricks) if bricks.is_empty() => _ = writeln!(report, "  none loaded"),
+        Some(bricks) => bricks.iter().for_each(|brick| _ = writeln!(report, "  {brick}")),
+        None => _ = writeln!(report, "  unavailable, the list was in use"),
+    }
+
+    let _ = writeln!(report, "\nActive hooks:");
+    match try_lock(&HOOKS) {
+        Some(hooks) if hooks.is_empty() => _ = writeln!(report, "  none"),
+        Some(hooks) => hooks.iter().for_each(|hook| _ = writeln!(report, "  {} at {}", hook.name, describe_address(hook.target))),
+        None => _ = writeln!(report, "  unavailable, the list was in use"),
+    }
+
+    let _ = writeln!(report, "\nLast {LOG_LINES} log lines:");
+    match MainLogger::try_instance() {
+        Some(logger) => {
+            let records = &logger.overlay.records;
+            for record in records.iter_from(records.next_id().saturating_sub(LOG_LINES)) {
+                let _ = writeln!(report, "{}", record.to_plain());
+            }
+        }
+        None => _ = writeln!(report, "  unavailable, the logger was in use"),
+    }
+
+    let folder = paths::crash_dir();
+    fs::create_dir_all(&folder)?;
+    let path = folder.join(format!("crash-{}.txt", now.format("%Y-%m-%d_%H-%M-%S")));
+    fs::write(&path, report)?;
+    Ok(path)
+}
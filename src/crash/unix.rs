@@ -0,0 +1,114 @@
+use std::{ffi::{CStr, c_int, c_void}, mem, path::PathBuf, ptr, sync::OnceLock};
+
+const SIGNALS: [c_int; 5] = [libc::SIGSEGV, libc::SIGBUS, libc::SIGILL, libc::SIGFPE, libc::SIGABRT];
+/// Writing a report formats and captures a backtrace, which needs more than the usual minimum
+const ALT_STACK_SIZE: usize = 256 * 1024;
+
+static PREVIOUS_ACTIONS: OnceLock<Vec<(c_int, libc::sigaction)>> = OnceLock::new();
+
+fn signal_name(signal: c_int) -> &'static str {
+    match signal {
+        libc::SIGSEGV => "SIGSEGV",
+        libc::SIGBUS => "SIGBUS",
+        libc::SIGILL => "SIGILL",
+        libc::SIGFPE => "SIGFPE",
+        libc::SIGABRT => "SIGABRT",
+        _ => "unknown",
+    }
+}
+
+/// The instruction that faulted, falling back to the address the signal is about where it can't be read
+fn instruction_pointer(info: *mut libc::siginfo_t, _context: *mut c_void) -> usize {
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    if !_context.is_null() {
+        return unsafe { (*_context.cast::<libc::ucontext_t>()).uc_mcontext.gregs[libc::REG_RIP as usize] } as usize;
+    }
+    #[cfg(all(target_os = "linux", target_arch = "x86"))]
+    if !_context.is_null() {
+        return unsafe { (*_context.cast::<libc::ucontext_t>()).uc_mcontext.gregs[libc::REG_EIP as usize] } as usize;
+    }
+
+    unsafe { (*info).si_addr() as usize }
+}
+
+extern "C" fn signal_handler(signal: c_int, info: *mut libc::siginfo_t, context: *mut c_void) {
+    let previous = PREVIOUS_ACTIONS.get().and_then(|actions| actions.iter().find(|(previous, _)| *previous == signal)).map(|(_, action)| action);
+
+    // runtimes like Mono, the JVM and Wine handle these signals themselves and often recover, so their handler gets the signal
+    // and this one stays installed. It is only a crash once nothing is left but the default action.
+    if let Some(action) = previous && action.sa_sigaction != libc::SIG_DFL && action.sa_sigaction != libc::SIG_IGN {
+        unsafe {
+            if action.sa_flags & libc::SA_SIGINFO != 0 {
+                let handler: extern "C" fn(c_int, *mut libc::siginfo_t, *mut c_void) = mem::transmute(action.sa_sigaction);
+                handler(signal, info, context);
+            } else {
+                let handler: extern "C" fn(c_int) = mem::transmute(action.sa_sigaction);
+                handler(signal);
+            }
+        }
+        return;
+    }
+
+    let accessed = unsafe { (*info).si_addr() as usize };
+    super::report(&format!("fatal signal {signal} ({}) accessing {accessed:#X}", signal_name(signal)), Some(instruction_pointer(info, context)));
+
+    // put back the default action, returning re-runs the faulting instruction and abort raises again
+    unsafe {
+        match previous {
+            Some(action) => _ = libc::sigaction(signal, action, ptr::null_mut()),
+            None => _ = libc::signal(signal, libc::SIG_DFL),
+        }
+    }
+}
+
+/// Gives the thread that installs the handler an alternate stack if it has none, so a stack overflow on it can still be reported.
+/// Threads spawned by std already have one, other threads run the handler on their own stack.
+fn install_alt_stack() {
+    unsafe {
+        let mut current: libc::stack_t = mem::zeroed();
+        if libc::sigaltstack(ptr::null(), &mut current) == 0 && current.ss_flags & libc::SS_DISABLE == 0 {
+            return;
+        }
+
+        let stack = Box::leak(vec![0u8; ALT_STACK_SIZE].into_boxed_slice());
+        let alt_stack = libc::stack_t {
+            ss_sp: stack.as_mut_ptr().cast(),
+            ss_flags: 0,
+            ss_size: ALT_STACK_SIZE,
+        };
+        libc::sigaltstack(&alt_stack, ptr::null_mut());
+    }
+}
+
+pub(super) fn install() {
+    install_alt_stack();
+
+    let mut previous_actions = Vec::new();
+
+    for signal in SIGNALS {
+        unsafe {
+            let mut action: libc::sigaction = mem::zeroed();
+            action.sa_sigaction = signal_handler as *const () as usize;
+            action.sa_flags = libc::SA_SIGINFO | libc::SA_ONSTACK;
+            libc::sigemptyset(&mut action.sa_mask);
+
+            let mut previous: libc::sigaction = mem::zeroed();
+            if libc::sigaction(signal, &action, &mut previous) == 0 {
+                previous_actions.push((signal, previous));
+            }
+        }
+    }
+
+    let _ = PREVIOUS_ACTIONS.set(previous_actions);
+}
+
+/// The path of the module containing the address and the address' offset into it
+pub(crate) fn module_at(address: usize) -> Option<(PathBuf, usize)> {
+    let mut info: libc::Dl_info = unsafe { mem::zeroed() };
+    if unsafe { libc::dladdr(address as *const c_void, &mut info) } == 0 || info.dli_fname.is_null() {
+        return None;
+    }
+
+    let name = unsafe { CStr::from_ptr(info.dli_fname) };
+    Some((PathBuf::from(name.to_string_lossy().into_owned()), address - info.dli_fbase as usize))
+}
@@ -0,0 +1,67 @@
+use std::{path::PathBuf, sync::OnceLock};
+
+use windows::{
+    Win32::{
+        Foundation::HMODULE,
+        System::{
+            Diagnostics::Debug::{EXCEPTION_POINTERS, LPTOP_LEVEL_EXCEPTION_FILTER, SetUnhandledExceptionFilter},
+            LibraryLoader::{GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS, GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT, GetModuleFileNameW, GetModuleHandleExW},
+        },
+    },
+    core::PCWSTR,
+};
+
+/// Lets the game's or Windows' own handling carry on after the report is written
+const EXCEPTION_CONTINUE_SEARCH: i32 = 0;
+
+static PREVIOUS_FILTER: OnceLock<LPTOP_LEVEL_EXCEPTION_FILTER> = OnceLock::new();
+
+fn exception_name(code: u32) -> &'static str {
+    match code {
+        0xC0000005 => "access violation",
+        0xC000001D => "illegal instruction",
+        0xC0000094 => "integer divide by zero",
+        0xC00000FD => "stack overflow",
+        0xC0000409 => "stack buffer overrun",
+        0x80000003 => "breakpoint",
+        _ => "unknown",
+    }
+}
+
+unsafe extern "system" fn exception_filter(info: *const EXCEPTION_POINTERS) -> i32 {
+    let record = unsafe { &*(*info).ExceptionRecord };
+    let code = record.ExceptionCode.0 as u32;
+
+    let mut cause = format!("unhandled exception {code:#010X} ({})", exception_name(code));
+    if code == 0xC0000005 && record.NumberParameters >= 2 {
+        let access = match record.ExceptionInformation[0] {
+            0 => "reading",
+            1 => "writing",
+            8 => "executing",
+            _ => "accessing",
+        };
+        cause += &format!(" while {access} {:#X}", record.ExceptionInformation[1]);
+    }
+    super::report(&cause, Some(record.ExceptionAddress as usize));
+
+    match PREVIOUS_FILTER.get().copied().flatten() {
+        Some(previous) => unsafe { previous(info) },
+        None => EXCEPTION_CONTINUE_SEARCH,
+    }
+}
+
+pub(super) fn install() {
+    let previous = unsafe { SetUnhandledExceptionFilter(Some(exception_filter)) };
+    let _ = PREVIOUS_FILTER.set(previous);
+}
+
+/// The path of the module containing the address and the address' offset into it
+pub(crate) fn module_at(address: usize) -> Option<(PathBuf, usize)> {
+    let mut module = HMODULE::default();
+    let flags = GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS | GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT;
+    unsafe { GetModuleHandleExW(flags, PCWSTR(address as *const u16), &mut module) }.ok()?;
+
+    let mut name = [0u16; 260];
+    let len = unsafe { GetModuleFileNameW(Some(module), &mut name) } as usize;
+    Some((PathBuf::from(String::from_utf16_lossy(&name[..len])), address - module.0 as usize))
+}
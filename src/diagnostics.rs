@@ -0,0 +1,214 @@
+use std::{collections::VecDeque, fmt, path::PathBuf, sync::{Mutex, MutexGuard, OnceLock}, time::{Duration, Instant}};
+
+use bluebrick::imgui::{TableFlags, Ui};
+use bluebrick_proxy::Config;
+
+use crate::paths;
+
+/// Why BlueBrick started with every brick disabled
+#[derive(Clone, Debug)]
+pub(crate) enum SafeModeReason {
+    KeyHeld,
+    Config,
+    Crashed(PathBuf),
+}
+
+impl fmt::Display for SafeModeReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SafeModeReason::KeyHeld => write!(f, "Shift was held while the game started"),
+            SafeModeReason::Config => write!(f, "safe mode is turned on in the config"),
+            SafeModeReason::Crashed(report) => write!(f, "the game crashed last time, see {}", report.display()),
+        }
+    }
+}
+
+/// Holding shift while the game starts asks for safe mode
+#[cfg(windows)]
+fn safe_mode_key_held() -> bool {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetAsyncKeyState, VK_SHIFT};
+
+    unsafe { GetAsyncKeyState(VK_SHIFT.0 as i32) as u16 & 0x8000 != 0 }
+}
+
+/// There is no key state to read before a window exists on other platforms
+#[cfg(not(windows))]
+fn safe_mode_key_held() -> bool {
+    false
+}
+
+impl SafeModeReason {
+    /// Checks the held key first, since it is the only one the player can change without editing files
+    pub fn detect(config: &Config, crash_report: Option<PathBuf>) -> Option<Self> {
+        if safe_mode_key_held() {
+            Some(SafeModeReason::KeyHeld)
+        } else if config.safe_mode {
+            Some(SafeModeReason::Config)
+        } else {
+            crash_report.map(SafeModeReason::Crashed)
+        }
+    }
+}
+
+/// How far a part of BlueBrick got while starting
+#[derive(Clone, Debug)]
+pub(crate) enum Status {
+    Starting,
+    Running,
+    Off(String),
+    Failed(String),
+}
+
+impl Status {
+    fn color(&self) -> [f32; 4] {
+        match self {
+            Status::Starting => [0.6, 0.6, 0.6, 1.0],
+            Status::Running => [0.3, 0.8, 0.3, 1.0],
+            Status::Off(_) => [0.85, 0.65, 0.13, 1.0],
+            Status::Failed(_) => [0.9, 0.25, 0.25, 1.0],
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Status::Starting => write!(f, "Starting"),
+            Status::Running => write!(f, "Running"),
+            Status::Off(reason) => write!(f, "Off: {reason}"),
+            Status::Failed(e) => write!(f, "Failed: {e}"),
+        }
+    }
+}
+
+/// How many recent frames the frame times are worked out from
+const FRAME_SAMPLES: usize = 300;
+
+/// How long the overlay took in each recent frame and how far apart the frames were,
+/// so changes to the frame path can be measured in the game
+pub(crate) struct FrameTimes {
+    /// The overlay's time and the time since the frame before, for each recent frame
+    samples: VecDeque<(Duration, Duration)>,
+    last_frame: Option<Instant>,
+}
+
+impl FrameTimes {
+    pub fn new() -> Self {
+        Self {
+            samples: VecDeque::with_capacity(FRAME_SAMPLES),
+            last_frame: None,
+        }
+    }
+
+    /// Records a frame whose overlay work started at `started` and just finished
+    pub fn record(&mut self, started: Instant) {
+        let overlay = started.elapsed();
+        let frame = self.last_frame.map(|last| started - last).unwrap_or_default();
+        self.last_frame = Some(started);
+
+        if self.samples.len() == FRAME_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((overlay, frame));
+    }
+
+    fn millis(duration: Duration) -> String {
+        format!("{:.3} ms", duration.as_secs_f64() * 1000.0)
+    }
+
+    pub fn draw(&self, ui: &Ui) {
+        if self.samples.is_empty() {
+            ui.text_disabled("No frames have been drawn yet");
+            return;
+        }
+
+        let count = self.samples.len() as u32;
+        let overlay = self.samples.iter().map(|(overlay, _)| *overlay);
+        let frames = self.samples.iter().skip(1).map(|(_, frame)| *frame);
+        let average_frame = frames.clone().sum::<Duration>() / (count - 1).max(1);
+
+        ui.text(format!("Over the last {count} frames:"));
+        if let Some(_table) = ui.begin_table_with_flags("FrameTimes", 3, TableFlags::BORDERS | TableFlags::ROW_BG) {
+            ui.table_setup_column("");
+            ui.table_setup_column("Average");
+            ui.table_setup_column("Worst");
+            ui.table_headers_row();
+
+            for (name, average, worst) in [
+                ("Overlay", overlay.clone().sum::<Duration>() / count, overlay.max().unwrap_or_default()),
+                ("Frame", average_frame, frames.max().unwrap_or_default()),
+            ] {
+                ui.table_next_column();
+                ui.text(name);
+                ui.table_next_column();
+                ui.text(Self::millis(average));
+                ui.table_next_column();
+                ui.text(Self::millis(worst));
+            }
+        }
+        if !average_frame.is_zero() {
+            ui.text_disabled(format!("{:.1} frames per second", 1.0 / average_frame.as_secs_f64()));
+        }
+    }
+}
+
+/// What happened during startup, shown in the diagnostics window so problems can be found without reading the log
+pub(crate) struct Diagnostics {
+    pub safe_mode: Option<SafeModeReason>,
+    pub overlay: Status,
+    pub hooks: Status,
+    pub bricks: Status,
+    pub config_problems: Vec<String>,
+}
+
+impl Diagnostics {
+    fn new() -> Self {
+        Self {
+            safe_mode: None,
+            overlay: Status::Starting,
+            hooks: Status::Starting,
+            bricks: Status::Starting,
+            config_problems: Vec::new(),
+        }
+    }
+
+    pub fn instance() -> MutexGuard<'static, Self> {
+        static DIAGNOSTICS: OnceLock<Mutex<Diagnostics>> = OnceLock::new();
+        DIAGNOSTICS.get_or_init(|| Mutex::new(Diagnostics::new())).lock().unwrap()
+    }
+
+    pub fn draw(&self, ui: &Ui) {
+        match &self.safe_mode {
+            Some(reason) => {
+                ui.text_colored([0.85, 0.65, 0.13, 1.0], format!("Safe mode: {reason}"));
+                ui.text_wrapped("Every brick was loaded disabled and the game hooks were skipped. Bricks can be enabled one at a time from Blue Brick > Show Bricks.");
+            }
+            None => ui.text("Started normally"),
+        }
+        ui.separator();
+
+        if let Some(_table) = ui.begin_table_with_flags("Subsystems", 2, TableFlags::BORDERS | TableFlags::ROW_BG) {
+            ui.table_setup_column("Subsystem");
+            ui.table_setup_column("Status");
+            ui.table_headers_row();
+
+            for (name, status) in [("Overlay", &self.overlay), ("Game hooks", &self.hooks), ("Bricks", &self.bricks)] {
+                ui.table_next_column();
+                ui.text(name);
+                ui.table_next_column();
+                ui.text_colored(status.color(), status.to_string());
+            }
+        }
+
+        ui.separator();
+        ui.text_wrapped(paths::describe());
+
+        if !self.config_problems.is_empty() {
+            ui.separator();
+            ui.text("Config problems:");
+            for problem in &self.config_problems {
+                ui.bullet_text(problem);
+            }
+        }
+    }
+}
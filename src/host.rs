@@ -0,0 +1,33 @@
+use std::ffi::c_char;
+
+use bluebrick::host::{HOST_API_VERSION, HostApi};
+
+use crate::logger::{log_library_color_impl, log_library_impl, log_mod_color_impl, log_mod_impl, levels::{register_library_level_impl, register_mod_level_impl}};
+use crate::overlay::notifications::{notify_library_color_impl, notify_library_impl, notify_mod_color_impl, notify_mod_impl};
+use crate::paths::{self, PathKind};
+
+extern "C" fn bluebrick_dir() -> *const c_char {
+    paths::dir_impl(PathKind::Root as u32)
+}
+
+pub(crate) static HOST_API: HostApi = HostApi {
+    version: HOST_API_VERSION,
+    loader_version: concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char,
+
+    log_library: log_library_impl,
+    log_mod: log_mod_impl,
+    notify_library: notify_library_impl,
+    notify_mod: notify_mod_impl,
+
+    bluebrick_dir,
+
+    register_library_level: register_library_level_impl,
+    register_mod_level: register_mod_level_impl,
+
+    dir: paths::dir_impl,
+
+    log_library_color: log_library_color_impl,
+    log_mod_color: log_mod_color_impl,
+    notify_library_color: notify_library_color_impl,
+    notify_mod_color: notify_mod_color_impl,
+};
@@ -1,132 +1,214 @@
+mod crash;
+mod diagnostics;
+mod host;
 pub mod logger;
 mod memutils;
 mod overlay;
+mod packages;
+mod paths;
+#[cfg(target_os = "linux")]
+mod preload;
+mod profiles;
 pub mod subbrick;
+mod trust;
 
-use std::{error::Error, fmt, mem, sync::{OnceLock, mpsc::{self, Sender}}, thread};
+use std::{sync::{Mutex, OnceLock, atomic::{AtomicBool, Ordering}}, thread};
 
-use bluebrick_proxy::Config;
-use retour::static_detour;
+use bluebrick_proxy::{Config, LegacyConfig};
+pub(crate) use bluebrick_proxy::show_error;
 
-use crate::logger::{main_log_debug, main_log_error};
-use crate::overlay::{Overlay, OverlayEvent, OverlayHandle};
+use crate::diagnostics::{Diagnostics, SafeModeReason, Status};
+use crate::logger::{MainLogger, levels::LogLevels, main_log, main_log_error, main_log_warning};
+use crate::overlay::{Overlay, OverlayHandle, notifications::main_notify};
+use crate::profiles::Profiles;
 use crate::subbrick::SubBrickManager;
+use crate::trust::Trust;
+
+/// The game's own hooks, which are found from the Windows executable
+#[cfg(windows)]
+fn hook() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    use std::mem;
+
+    use retour::static_detour;
+
+    use crate::logger::main_log_trace;
 
-fn hook() -> std::result::Result<(), Box<dyn Error>> {
     static_detour! {
         static AddToCoins: unsafe extern "cdecl" fn(*mut u64, u64, i32, bool);
         static AddToCoins2: unsafe extern "cdecl" fn(*mut u64, u64, i32, bool);
     }
 
-    let real_add_to_coins = memutils::get_executable_base()? + 0x7E1070;
-    let real_add_to_coins = unsafe { mem::transmute(real_add_to_coins) };
+    let add_to_coins_address = memutils::get_executable_base()? + 0x7E1070;
+    let real_add_to_coins = unsafe { mem::transmute(add_to_coins_address) };
 
     unsafe {
         AddToCoins.initialize(real_add_to_coins, move |coins_ptr, to_add, mult, round_to_10s| {
-            main_log_debug!("1: Got coin worth: {to_add}, mult: {mult}");
+            main_log_trace!("1: Got coin worth: {to_add}, mult: {mult}");
             AddToCoins.call(coins_ptr, 0, mult, round_to_10s);
         })?;
         AddToCoins.enable()?;
+        crash::add_hook("AddToCoins", add_to_coins_address);
         AddToCoins2.initialize(real_add_to_coins, move |coins_ptr, to_add, mult, round_to_10s| {
-            main_log_debug!("2: Got coin worth: {to_add}, mult: {mult}");
+            main_log_trace!("2: Got coin worth: {to_add}, mult: {mult}");
             AddToCoins2.call(coins_ptr, 0, mult, round_to_10s);
         })?;
         AddToCoins2.enable()?;
+        crash::add_hook("AddToCoins2", add_to_coins_address);
     }
 
     Ok(())
 }
 
-type Result<T> = std::result::Result<T, StartupErr>;
-
-#[derive(Debug)]
-enum StartupErr {
-    Overlay(Box<dyn Error>),
-    Hooks(Box<dyn Error>),
-}
-
-impl fmt::Display for StartupErr {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        use StartupErr::*;
-        let message = match self {
-            Overlay(e) => format!("Problem attaching imgui: {e}"),
-            Hooks(e) => format!("Problem hooking functions: {e}"),
-        };
-        write!(f, "Error starting up BlueBrick: {}", message)
-    }
-}
-
-pub enum BBEvent {
-    Overlay(OverlayEvent),
-    SubBrick//(SubBrickEvent),
-}
-
 struct BlueBrick {
-    overlay: Overlay,
+    /// None when the overlay is turned off in the config or failed to start
+    overlay: Option<Overlay>,
     subbrick_manager: SubBrickManager,
 }
 
+// imgui's context and the bricks are only used with the handle's lock held, by whichever thread holds it,
+// and imgui keeps its current context in a global rather than per thread
+unsafe impl Send for BlueBrick {}
+
 impl BlueBrick {
-    fn new(config: Config) -> Result<Self> {
-        let overlay = match Overlay::new(config) {
-            Ok(overlay) => overlay,
-            Err(e) => return Err(StartupErr::Overlay(e)),
+    /// Starts each part on its own, so one failing only loses that part
+    fn new(config: Config, safe_mode: bool) -> Self {
+        let overlay = match config.overlay_enabled {
+            true => match Overlay::new(config, safe_mode) {
+                Ok(overlay) => {
+                    Diagnostics::instance().overlay = Status::Running;
+                    Some(overlay)
+                }
+                Err(e) => {
+                    main_log_error!("Problem attaching imgui, continuing without the overlay: {e}");
+                    Diagnostics::instance().overlay = Status::Failed(e.to_string());
+                    show_error("BlueBrick Overlay Failed To Start", &format!("Continuing to the game without the overlay:\n{e}"));
+                    None
+                }
+            },
+            false => {
+                Diagnostics::instance().overlay = Status::Off(String::from("turned off in the config"));
+                None
+            }
         };
 
-        if let Err(e) = hook() {
-            return Err(StartupErr::Hooks(e));
-        }
+        Diagnostics::instance().hooks = match safe_mode {
+            true => Status::Off(String::from("safe mode")),
+            false => Self::start_hooks(),
+        };
 
-        let subbrick_manager = SubBrickManager::new();
+        let trust = Trust::new(config.unsigned_bricks, paths::config_dir());
+        let profiles = Profiles::new(paths::config_dir());
+        let subbrick_manager = SubBrickManager::new(paths::brick_dir(), safe_mode, trust, profiles, config.profile());
+        crash::set_bricks(subbrick_manager.brick_infos());
+        MainLogger::instance().set_loaded_bricks(subbrick_manager.brick_infos());
+        Diagnostics::instance().bricks = match safe_mode {
+            true => Status::Off(String::from("safe mode, the bricks were listed but not loaded")),
+            false => Status::Running,
+        };
 
-        Ok(BlueBrick {
+        BlueBrick {
             overlay,
             subbrick_manager
-        })
+        }
     }
 
-    fn handle_event(&mut self, event: BBEvent) {
-        match event {
-            BBEvent::Overlay(event) => self.overlay.handle_event(event, &mut self.subbrick_manager),
-            BBEvent::SubBrick => {}//(subbrick) => self.subbrick_manager.handle_event(event),
+    #[cfg(windows)]
+    fn start_hooks() -> Status {
+        match hook() {
+            Ok(()) => Status::Running,
+            Err(e) => {
+                main_log_error!("Problem hooking functions, continuing without them: {e}");
+                Status::Failed(e.to_string())
+            }
         }
     }
+
+    #[cfg(not(windows))]
+    fn start_hooks() -> Status {
+        Status::Off(String::from("the game hooks are only written for the Windows version of the game"))
+    }
 }
 
 pub struct BlueBrickHandle {
-    overlay: OverlayHandle,
-    #[allow(unused)]
-    tx: Sender<BBEvent>,
+    /// Drawn on the render thread each frame
+    bluebrick: Mutex<BlueBrick>,
+    #[cfg_attr(not(backend_dx9), allow(unused))]
+    overlay: Option<OverlayHandle>,
 }
 
 pub static BLUEBRICK_HANDLE: OnceLock<BlueBrickHandle> = OnceLock::new();
 impl BlueBrickHandle {
+    /// Runs `f` with the overlay and the bricks on the calling thread, returns None if the overlay isn't running
+    #[cfg_attr(not(backend_dx9), allow(unused))]
+    pub(crate) fn with_overlay<R>(&self, f: impl FnOnce(&mut Overlay, &mut SubBrickManager) -> R) -> Option<R> {
+        let mut bb = self.bluebrick.lock().unwrap();
+        let BlueBrick { overlay, subbrick_manager } = &mut *bb;
+        overlay.as_mut().map(|overlay| f(overlay, subbrick_manager))
+    }
+
+    /// Only the first call does anything, in case the library is both preloaded and loaded by a proxy
     fn start(config: Config) {
-        let (tx, rx) = mpsc::channel();
+        static STARTED: AtomicBool = AtomicBool::new(false);
+        if STARTED.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        paths::init(&config);
+        crash::install();
+
+        MainLogger::instance().set_config(config);
+        main_log!("Paths: {}", paths::describe());
+        let mut config_problems = config.problems();
+        config_problems.extend(LogLevels::instance().configure(&config.log_level().unwrap_or_default()));
+        for problem in &config_problems {
+            main_log_warning!("Config: {problem}");
+        }
+        if !config.overlay_enabled {
+            main_log!("The overlay is turned off in the config");
+        }
+
+        let crash_report = crash::take_marker();
+        if let Some(report) = &crash_report {
+            main_notify!(Error, true, "The game crashed last time", "A crash report was written to {}", report.display());
+        }
+
+        let safe_mode = SafeModeReason::detect(&config, crash_report);
+        if let Some(reason) = &safe_mode {
+            main_log_warning!("Starting in safe mode because {reason}");
+        }
+        {
+            let mut diagnostics = Diagnostics::instance();
+            diagnostics.config_problems = config_problems;
+            diagnostics.safe_mode = safe_mode.clone();
+        }
 
+        // loading the bricks can take a while, so the game carries on meanwhile
         thread::spawn(move || {
-            let mut bb = match BlueBrick::new(config) {
-                Ok(bb) => bb,
-                Err(e) => {
-                    main_log_error!("{e}");
-                    let _ = msgbox::create("BlueBrick Failed To Start", "Continuing to the game without BlueBrick", msgbox::IconType::Info);
-                    return;
-                }
-            };
+            let bb = BlueBrick::new(config, safe_mode.is_some());
 
-            while let Ok(msg) = rx.recv() {
-                bb.handle_event(msg);
-            }
-        });
-        
-        _ = BLUEBRICK_HANDLE.set(Self {
-            overlay: OverlayHandle::new(config, tx.clone()),
-            tx
+            // the hooks check for the handle, so they pass straight through until everything they need exists
+            BLUEBRICK_HANDLE.get_or_init(|| Self {
+                overlay: bb.overlay.is_some().then(|| OverlayHandle::new(config)),
+                bluebrick: Mutex::new(bb),
+            });
         });
     }
 }
 
+/// Started by proxies from before the config had a size, which pass only the platform and renderer,
+/// so `config.toml` and the environment are read here instead
 #[unsafe(no_mangle)]
-extern "C" fn start_bluebrick(config: Config) {
-    BlueBrickHandle::start(config);
+extern "C" fn start_bluebrick(config: LegacyConfig) {
+    match config.read() {
+        Ok(config) => BlueBrickHandle::start(bluebrick_proxy::read_config(config, &paths::loader_dir())),
+        Err(e) => show_error("BlueBrick Failed To Start", &format!("Continuing to the game without BlueBrick, {e}")),
+    }
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn start_bluebrick_sized(config: *const Config) {
+    match unsafe { Config::read(config) } {
+        Ok(config) => BlueBrickHandle::start(config),
+        Err(e) => show_error("BlueBrick Failed To Start", &format!("Continuing to the game without BlueBrick, {e}")),
+    }
 }
@@ -0,0 +1,203 @@
+use std::{collections::BTreeMap, env, fs::{self, File}, io::{self, Write}, path::{Path, PathBuf}};
+
+use bluebrick_proxy::Config;
+use chrono::{DateTime, Local};
+
+use super::record::LogRecord;
+use super::sinks::LogSink;
+use crate::paths;
+
+const LATEST_NAME: &str = "latest.log";
+const DEFAULT_RETENTION: usize = 10;
+const DEFAULT_MAX_SIZE: u64 = 16 * 1024 * 1024;
+/// Sorts the same as the time it stands for, so session files sort by when they started
+const STAMP_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+
+/// What is known about the running session, written at the top of every log file
+#[derive(Default, Clone)]
+pub(crate) struct SessionInfo {
+    pub platform: Option<String>,
+    pub renderer: Option<String>,
+    pub bricks: Option<Vec<String>>,
+}
+
+impl SessionInfo {
+    fn write_header(&self, file: &mut File, started: &DateTime<Local>, part: u32) -> io::Result<()> {
+        let executable = env::current_exe().map(|path| path.display().to_string()).unwrap_or_else(|e| format!("unknown ({e})"));
+        let unknown = String::from("unknown");
+
+        writeln!(file, "==== BlueBrick session log ====")?;
+        writeln!(file, "Loader version: {}", env!("CARGO_PKG_VERSION"))?;
+        writeln!(file, "Game executable: {executable}")?;
+        writeln!(file, "Platform: {}", self.platform.as_ref().unwrap_or(&unknown))?;
+        writeln!(file, "Renderer: {}", self.renderer.as_ref().unwrap_or(&unknown))?;
+        writeln!(file, "Session started: {}", started.format("%F %T"))?;
+        writeln!(file, "Part: {part}")?;
+        match &self.bricks {
+            Some(bricks) => Self::write_bricks(file, bricks)?,
+            None => writeln!(file, "Bricks: not loaded yet")?,
+        }
+        writeln!(file, "===============================")
+    }
+
+    fn write_bricks(file: &mut File, bricks: &[String]) -> io::Result<()> {
+        writeln!(file, "Bricks: {}", bricks.len())?;
+        for brick in bricks {
+            writeln!(file, "  {brick}")?;
+        }
+        Ok(())
+    }
+}
+
+/// How many sessions are kept and how big a file gets before the session continues in a new part
+#[derive(Clone, Copy)]
+pub(crate) struct FileLimits {
+    pub retention: usize,
+    pub max_size: u64,
+}
+
+impl Default for FileLimits {
+    fn default() -> Self {
+        Self {
+            retention: DEFAULT_RETENTION,
+            max_size: DEFAULT_MAX_SIZE,
+        }
+    }
+}
+
+impl FileLimits {
+    pub fn from_config(config: &Config) -> Self {
+        let default = Self::default();
+        Self {
+            retention: config.log_retention().map_or(default.retention, |retention| retention as usize),
+            max_size: config.log_max_size().unwrap_or(default.max_size),
+        }
+    }
+}
+
+/// The current session's log file, rotated once it gets too big, and its copy as `latest.log`
+pub(crate) struct LogFiles {
+    folder: PathBuf,
+    started: DateTime<Local>,
+    part: u32,
+    session: File,
+    latest: File,
+    written: u64,
+    max_size: u64,
+    retention: usize,
+    info: SessionInfo,
+}
+
+impl LogFiles {
+    /// Opens the session's log files, falling back to the temp folder if the game's folder can't be written to
+    pub fn open(info: &SessionInfo, limits: FileLimits) -> io::Result<Self> {
+        let started = Local::now();
+        match Self::open_in(paths::log_dir().to_path_buf(), started, info, limits) {
+            Ok(files) => Ok(files),
+            Err(e) => Self::open_in(env::temp_dir().join("bluebrick").join("logs"), started, info, limits).map_err(|_| e),
+        }
+    }
+
+    fn open_in(folder: PathBuf, started: DateTime<Local>, info: &SessionInfo, limits: FileLimits) -> io::Result<Self> {
+        fs::create_dir_all(&folder)?;
+
+        let (session, latest) = Self::create_files(&folder, &started, 1, info)?;
+
+        let files = Self {
+            folder,
+            started,
+            part: 1,
+            session,
+            latest,
+            written: 0,
+            max_size: limits.max_size,
+            // the current session always counts as one
+            retention: limits.retention.max(1),
+            info: info.clone(),
+        };
+        files.prune();
+
+        Ok(files)
+    }
+
+    fn session_path(folder: &Path, started: &DateTime<Local>, part: u32) -> PathBuf {
+        let stamp = started.format(STAMP_FORMAT);
+        match part {
+            1 => folder.join(format!("bluebrick-{stamp}.log")),
+            part => folder.join(format!("bluebrick-{stamp}.{part}.log")),
+        }
+    }
+
+    fn create_files(folder: &Path, started: &DateTime<Local>, part: u32, info: &SessionInfo) -> io::Result<(File, File)> {
+        let mut session = File::create(Self::session_path(folder, started, part))?;
+        let mut latest = File::create(folder.join(LATEST_NAME))?;
+        info.write_header(&mut session, started, part)?;
+        info.write_header(&mut latest, started, part)?;
+        Ok((session, latest))
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        if self.written >= self.max_size {
+            self.rotate()?;
+        }
+
+        writeln!(self.session, "{line}")?;
+        writeln!(self.latest, "{line}")?;
+        self.written += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.part += 1;
+        (self.session, self.latest) = Self::create_files(&self.folder, &self.started, self.part, &self.info)?;
+        self.written = 0;
+        self.prune();
+        Ok(())
+    }
+
+    /// Deletes every part of the oldest sessions past the retention count, never the current session's
+    fn prune(&self) {
+        let Ok(entries) = fs::read_dir(&self.folder) else {
+            return;
+        };
+
+        let current = self.started.format(STAMP_FORMAT).to_string();
+        let mut sessions = BTreeMap::<String, Vec<PathBuf>>::new();
+        for path in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+            let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            if let Some(stamp) = session_stamp(&name)
+                && stamp != current
+            {
+                sessions.entry(String::from(stamp)).or_default().push(path);
+            }
+        }
+
+        let excess = sessions.len().saturating_sub(self.retention - 1);
+        for path in sessions.into_values().take(excess).flatten() {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// The start time a session file is named after, shared by all of the session's parts
+fn session_stamp(name: &str) -> Option<&str> {
+    let stem = name.strip_prefix("bluebrick-")?.strip_suffix(".log")?;
+    stem.split('.').next()
+}
+
+impl LogSink for LogFiles {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn write(&mut self, record: &LogRecord) -> io::Result<()> {
+        self.write_line(&record.to_plain())
+    }
+
+    /// Records the bricks once they are loaded, since the first file's header is written before that
+    fn bricks_loaded(&mut self, bricks: &[String]) -> io::Result<()> {
+        self.info.bricks = Some(Vec::from(bricks));
+        SessionInfo::write_bricks(&mut self.session, bricks)?;
+        SessionInfo::write_bricks(&mut self.latest, bricks)
+    }
+}
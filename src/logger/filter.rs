@@ -0,0 +1,253 @@
+use std::{collections::{BTreeSet, HashSet, VecDeque}, ops::Range};
+
+use bluebrick::{imgui::Ui, logger::Severity};
+use regex::{Regex, RegexBuilder};
+
+use super::record::{LogBuffer, LogRecord};
+
+pub(crate) struct LogFilter {
+    pub show_trace: bool,
+    pub show_info: bool,
+    pub show_debug: bool,
+    pub show_warning: bool,
+    pub show_error: bool,
+    pub show_loader: bool,
+    pub show_library: bool,
+    pub show_mod: bool,
+    pub hidden_names: HashSet<String>,
+    pub search: String,
+    pub use_regex: bool,
+    pub case_sensitive: bool,
+    pub auto_scroll: bool,
+    pub selected: BTreeSet<u64>,
+    /// Ids of the records that pass the filter, oldest first
+    pub visible: VecDeque<u64>,
+    /// A record to scroll the log window to on the next draw
+    pub scroll_to: Option<u64>,
+    last_clicked: Option<u64>,
+    search_regex: Option<Regex>,
+    search_error: Option<String>,
+    compiled_for: (String, bool, bool),
+    /// The id of the first record not yet checked against the filter
+    scanned_to: u64,
+    dirty: bool,
+}
+
+impl LogFilter {
+    pub fn new() -> Self {
+        Self {
+            show_trace: true,
+            show_info: true,
+            show_debug: true,
+            show_warning: true,
+            show_error: true,
+            show_loader: true,
+            show_library: true,
+            show_mod: true,
+            hidden_names: HashSet::new(),
+            search: String::new(),
+            use_regex: false,
+            case_sensitive: false,
+            auto_scroll: true,
+            selected: BTreeSet::new(),
+            visible: VecDeque::new(),
+            scroll_to: None,
+            last_clicked: None,
+            search_regex: None,
+            search_error: None,
+            compiled_for: (String::new(), false, false),
+            scanned_to: 0,
+            dirty: true,
+        }
+    }
+
+    fn update_search(&mut self) {
+        let key = (self.search.clone(), self.use_regex, self.case_sensitive);
+        if key == self.compiled_for {
+            return;
+        }
+        self.compiled_for = key;
+        self.dirty = true;
+
+        if self.search.is_empty() {
+            self.search_regex = None;
+            self.search_error = None;
+            return;
+        }
+
+        let pattern = if self.use_regex { self.search.clone() } else { regex::escape(&self.search) };
+        match RegexBuilder::new(&pattern).case_insensitive(!self.case_sensitive).build() {
+            Ok(regex) => {
+                self.search_regex = Some(regex);
+                self.search_error = None;
+            }
+            Err(e) => {
+                self.search_regex = None;
+                self.search_error = Some(e.to_string());
+            }
+        }
+    }
+
+    fn shows_severity(&self, severity: Severity) -> bool {
+        match severity {
+            Severity::Trace => self.show_trace,
+            Severity::Info => self.show_info,
+            Severity::Debug => self.show_debug,
+            Severity::Warning => self.show_warning,
+            Severity::Error => self.show_error,
+        }
+    }
+
+    fn shows_kind(&self, kind: &str) -> bool {
+        match kind {
+            "Loader" => self.show_loader,
+            "Library" => self.show_library,
+            "Mod" => self.show_mod,
+            _ => true,
+        }
+    }
+
+    fn matches(&self, record: &LogRecord) -> bool {
+        self.shows_severity(record.severity)
+            && self.shows_kind(record.kind)
+            && !self.hidden_names.contains(&record.name)
+            && self.search_regex.as_ref().is_none_or(|regex| regex.is_match(&record.to_plain()))
+    }
+
+    /// Brings `visible` up to date, only checking records added since the last call unless the filter changed
+    pub fn refresh(&mut self, records: &LogBuffer) {
+        if self.dirty {
+            self.visible.clear();
+            self.scanned_to = records.first_id();
+            self.dirty = false;
+        }
+
+        let first_id = records.first_id();
+        while self.visible.front().is_some_and(|&id| id < first_id) {
+            self.visible.pop_front();
+        }
+        self.selected.retain(|&id| id >= first_id);
+
+        for record in records.iter_from(self.scanned_to) {
+            if self.matches(record) {
+                self.visible.push_back(record.id);
+            }
+        }
+        self.scanned_to = records.next_id();
+    }
+
+    /// Clears the filters so a record is shown, then selects it and scrolls to it
+    pub fn reveal(&mut self, id: u64) {
+        self.show_trace = true;
+        self.show_info = true;
+        self.show_debug = true;
+        self.show_warning = true;
+        self.show_error = true;
+        self.show_loader = true;
+        self.show_library = true;
+        self.show_mod = true;
+        self.hidden_names.clear();
+        self.search.clear();
+        self.auto_scroll = false;
+        self.dirty = true;
+
+        self.selected.clear();
+        self.selected.insert(id);
+        self.last_clicked = Some(id);
+        self.scroll_to = Some(id);
+    }
+
+    pub fn find_matches(&self, text: &str) -> Vec<Range<usize>> {
+        match &self.search_regex {
+            Some(regex) => regex.find_iter(text).filter(|m| !m.is_empty()).map(|m| m.range()).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Updates the selection for a click on a shown record
+    pub fn click(&mut self, id: u64, ctrl: bool, shift: bool) {
+        match (ctrl, shift, self.last_clicked) {
+            (_, true, Some(last)) => {
+                let from = self.visible.binary_search(&last);
+                let to = self.visible.binary_search(&id);
+                if let (Ok(from), Ok(to)) = (from, to) {
+                    if !ctrl {
+                        self.selected.clear();
+                    }
+                    self.selected.extend(self.visible.range(from.min(to)..=from.max(to)));
+                    return;
+                }
+                self.selected.clear();
+                self.selected.insert(id);
+            }
+            (true, _, _) => {
+                if !self.selected.remove(&id) {
+                    self.selected.insert(id);
+                }
+            }
+            _ => {
+                self.selected.clear();
+                self.selected.insert(id);
+            }
+        }
+        self.last_clicked = Some(id);
+    }
+
+    pub fn draw_toolbar<'a>(&mut self, ui: &Ui, names: impl Iterator<Item = &'a String>) {
+        let mut changed = false;
+
+        changed |= ui.checkbox("Trace", &mut self.show_trace);
+        ui.same_line();
+        changed |= ui.checkbox("Info", &mut self.show_info);
+        ui.same_line();
+        changed |= ui.checkbox("Debug", &mut self.show_debug);
+        ui.same_line();
+        changed |= ui.checkbox("Warning", &mut self.show_warning);
+        ui.same_line();
+        changed |= ui.checkbox("Error", &mut self.show_error);
+
+        ui.same_line();
+        ui.text_disabled("|");
+        ui.same_line();
+        changed |= ui.checkbox("Loader", &mut self.show_loader);
+        ui.same_line();
+        changed |= ui.checkbox("Libraries", &mut self.show_library);
+        ui.same_line();
+        changed |= ui.checkbox("Mods", &mut self.show_mod);
+
+        ui.same_line();
+        if ui.button("Sources") {
+            ui.open_popup("LogSources");
+        }
+        ui.popup("LogSources", || {
+            for name in names {
+                let mut shown = !self.hidden_names.contains(name);
+                if ui.checkbox(name, &mut shown) {
+                    changed = true;
+                    if shown {
+                        self.hidden_names.remove(name);
+                    } else {
+                        self.hidden_names.insert(name.clone());
+                    }
+                }
+            }
+        });
+
+        ui.set_next_item_width(300.0);
+        ui.input_text("##LogSearch", &mut self.search).hint("Search").build();
+        ui.same_line();
+        ui.checkbox("Regex", &mut self.use_regex);
+        ui.same_line();
+        ui.checkbox("Match case", &mut self.case_sensitive);
+        ui.same_line();
+        ui.checkbox("Auto-scroll", &mut self.auto_scroll);
+
+        self.dirty |= changed;
+        self.update_search();
+        if let Some(e) = &self.search_error {
+            ui.text_colored([1.0, 0.3, 0.3, 1.0], format!("Invalid regex: {e}"));
+        }
+
+        ui.separator();
+    }
+}
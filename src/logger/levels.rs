@@ -0,0 +1,137 @@
+use std::{collections::BTreeMap, ffi::{CStr, c_char}, sync::{Mutex, MutexGuard, OnceLock, atomic::{AtomicU8, Ordering}}};
+
+use bluebrick::{imgui::Ui, logger::Severity};
+
+const DEFAULT_LEVEL: Severity = Severity::Debug;
+const LOADER_NAME: &str = "BlueBrick";
+
+static LOADER_LEVEL: AtomicU8 = AtomicU8::new(DEFAULT_LEVEL.rank());
+
+/// Checked by the `main_log` macros before formatting or locking the logger
+pub(crate) fn loader_enabled(severity: Severity) -> bool {
+    severity.rank() >= LOADER_LEVEL.load(Ordering::Relaxed)
+}
+
+struct Source {
+    kind: &'static str,
+    name: String,
+    /// Owned by the brick, which checks it before logging
+    min_level: &'static AtomicU8,
+}
+
+/// The minimum level of the loader and of every brick that registered one
+pub(crate) struct LogLevels {
+    /// Used for sources that weren't given their own level
+    default: Severity,
+    /// Levels from config by source name, applied when the source registers
+    configured: BTreeMap<String, Severity>,
+    sources: Vec<Source>,
+}
+
+impl LogLevels {
+    fn new() -> Self {
+        Self {
+            default: DEFAULT_LEVEL,
+            configured: BTreeMap::new(),
+            sources: Vec::new(),
+        }
+    }
+
+    pub fn instance() -> MutexGuard<'static, Self> {
+        static LOG_LEVELS: OnceLock<Mutex<LogLevels>> = OnceLock::new();
+        LOG_LEVELS.get_or_init(|| Mutex::new(LogLevels::new())).lock().unwrap()
+    }
+
+    /// Reads levels like `info,BlueBrick=debug,SomeMod=trace,mod:OtherMod=warning`, where a bare level is the default for every source.
+    /// A name can start with `library:` or `mod:` to only match that kind of brick.
+    /// Returns the parts that couldn't be understood.
+    pub fn configure(&mut self, config: &str) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        for part in config.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+            let (name, level) = match part.split_once('=') {
+                Some((name, level)) => (Some(name.trim()), level),
+                None => (None, part),
+            };
+
+            let Some(level) = Severity::from_name(level) else {
+                problems.push(format!("Unknown log level {level:?} in {part:?}"));
+                continue;
+            };
+
+            match name {
+                Some(name) => {
+                    let name = if name.eq_ignore_ascii_case("loader") { LOADER_NAME } else { name };
+                    self.configured.insert(qualified_name(name), level);
+                }
+                None => self.default = level,
+            }
+        }
+
+        LOADER_LEVEL.store(self.configured_level("Loader", LOADER_NAME).rank(), Ordering::Relaxed);
+        for source in &self.sources {
+            source.min_level.store(self.configured_level(source.kind, &source.name).rank(), Ordering::Relaxed);
+        }
+
+        problems
+    }
+
+    /// A level given for the kind and name wins over one given for just the name
+    fn configured_level(&self, kind: &str, name: &str) -> Severity {
+        self.configured.get(&format!("{kind}:{name}")).or_else(|| self.configured.get(name)).copied().unwrap_or(self.default)
+    }
+
+    fn register(&mut self, kind: &'static str, name: String, min_level: &'static AtomicU8) {
+        min_level.store(self.configured_level(kind, &name).rank(), Ordering::Relaxed);
+        self.sources.retain(|source| source.kind != kind || source.name != name);
+        self.sources.push(Source { kind, name, min_level });
+    }
+
+    fn draw_level(ui: &Ui, label: &str, min_level: &AtomicU8) {
+        let names = Severity::ALL.map(Severity::name);
+        let mut index = min_level.load(Ordering::Relaxed) as usize;
+
+        ui.set_next_item_width(150.0);
+        if ui.combo_simple_string(label, &mut index, &names) {
+            min_level.store(Severity::ALL[index].rank(), Ordering::Relaxed);
+        }
+    }
+
+    /// Draws a level picker for the loader and each brick, changes apply immediately
+    pub fn draw(&mut self, ui: &Ui) {
+        ui.text_disabled("Messages below a source's level are dropped before they are formatted");
+        ui.separator();
+
+        Self::draw_level(ui, &format!("{LOADER_NAME} (Loader)"), &LOADER_LEVEL);
+
+        for source in &self.sources {
+            Self::draw_level(ui, &format!("{} ({})##{}", source.name, source.kind, source.kind), source.min_level);
+        }
+    }
+}
+
+/// Spells a `library:` or `mod:` prefix the way sources are registered, so it matches whatever case it was written in
+fn qualified_name(name: &str) -> String {
+    for kind in ["Library", "Mod"] {
+        if let Some((prefix, name)) = name.split_once(':')
+            && prefix.trim().eq_ignore_ascii_case(kind)
+        {
+            return format!("{kind}:{}", name.trim());
+        }
+    }
+    String::from(name)
+}
+
+fn register_impl(kind: &'static str, name: *const c_char, min_level: &'static AtomicU8) {
+    let name = unsafe { CStr::from_ptr(name) };
+    let name = String::from_utf8_lossy(name.to_bytes()).to_string();
+    LogLevels::instance().register(kind, name, min_level);
+}
+
+pub(crate) extern "C" fn register_library_level_impl(name: *const c_char, min_level: &'static AtomicU8) {
+    register_impl("Library", name, min_level);
+}
+
+pub(crate) extern "C" fn register_mod_level_impl(name: *const c_char, min_level: &'static AtomicU8) {
+    register_impl("Mod", name, min_level);
+}
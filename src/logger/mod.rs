@@ -1,26 +1,47 @@
 #[cfg(windows)]
 mod windows;
 
-pub mod web_colors;
+pub mod files;
+pub mod filter;
+pub mod levels;
+pub mod record;
+pub mod sinks;
 
-use std::{env, ffi::{CStr, c_char}, fs::File, io::Write, sync::{LazyLock, Mutex, MutexGuard, OnceLock}};
+use std::{ffi::{CStr, c_char}, ops::Range, sync::{Mutex, MutexGuard, OnceLock, TryLockError}};
 
-use bluebrick::{imgui::{StyleColor, StyleVar, Ui}, logger::Severity};
-use colored::{Color, ColoredString, Colorize};
-use regex::Regex;
-use web_colors::WebColor;
+use bluebrick::{imgui::{Key, ListClipper, MouseButton, StyleColor, StyleVar, Ui}, logger::Severity, color::Color, web_colors::{Rgb, WebColor}};
+use bluebrick_proxy::Config;
+use files::{FileLimits, SessionInfo};
+use filter::LogFilter;
+use record::{LogBuffer, LogRecord};
+use sinks::{LogSink, OverlaySink, SinkKind};
 
 macro_rules! main_log {
     ($($arg:tt)*) => {
-        crate::logger::MainLogger::instance().log_with_severity(&format!($($arg)*), bluebrick::logger::Severity::Info);
+        if crate::logger::levels::loader_enabled(bluebrick::logger::Severity::Info) {
+            crate::logger::MainLogger::instance().log_with_severity(&format!($($arg)*), bluebrick::logger::Severity::Info);
+        }
     }
 }
 pub(crate) use main_log;
 
+#[allow(unused_macros)]
+macro_rules! main_log_trace {
+    ($($arg:tt)*) => {
+        if crate::logger::levels::loader_enabled(bluebrick::logger::Severity::Trace) {
+            crate::logger::MainLogger::instance().log_with_severity(&format!($($arg)*), bluebrick::logger::Severity::Trace);
+        }
+    }
+}
+#[allow(unused_imports)]
+pub(crate) use main_log_trace;
+
 #[allow(unused_macros)]
 macro_rules! main_log_debug {
     ($($arg:tt)*) => {
-        crate::logger::MainLogger::instance().log_with_severity(&format!($($arg)*), bluebrick::logger::Severity::Debug);
+        if crate::logger::levels::loader_enabled(bluebrick::logger::Severity::Debug) {
+            crate::logger::MainLogger::instance().log_with_severity(&format!($($arg)*), bluebrick::logger::Severity::Debug);
+        }
     }
 }
 #[allow(unused_imports)]
@@ -28,264 +49,286 @@ pub(crate) use main_log_debug;
 
 macro_rules! main_log_warning {
     ($($arg:tt)*) => {
-        crate::logger::MainLogger::instance().log_with_severity(&format!($($arg)*), bluebrick::logger::Severity::Warning);
+        if crate::logger::levels::loader_enabled(bluebrick::logger::Severity::Warning) {
+            crate::logger::MainLogger::instance().log_with_severity(&format!($($arg)*), bluebrick::logger::Severity::Warning);
+        }
     }
 }
 pub(crate) use main_log_warning;
 
 macro_rules! main_log_error {
     ($($arg:tt)*) => {
-        crate::logger::MainLogger::instance().log_with_severity(&format!($($arg)*), bluebrick::logger::Severity::Error);
+        if crate::logger::levels::loader_enabled(bluebrick::logger::Severity::Error) {
+            crate::logger::MainLogger::instance().log_with_severity(&format!($($arg)*), bluebrick::logger::Severity::Error);
+        }
     }
 }
 pub(crate) use main_log_error;
 
-pub(crate) enum LogItem {
-    Text(String),
-    NewLine,
-    Color(Color),
-    StyleReset,
-}
-
 pub(crate) struct MainLogger {
-    pub log_items: Vec<LogItem>,
+    pub overlay: OverlaySink,
     pub log_scroll_changed: bool,
-    file: File,
+    session: SessionInfo,
+    /// The config's sinks and log server address, None for the defaults
+    sink_names: Option<String>,
+    server_address: Option<String>,
+    file_limits: FileLimits,
+    /// Opened on the first message, so the file headers can include the config
+    sinks: Option<Vec<Box<dyn LogSink>>>,
 }
 
 impl MainLogger {
-    // TODO: don't panic on fail
     fn new() -> Self {
-        #[cfg(windows)] {
-            if let Err(e) = windows::init_terminal() {
-                let msg = format!("Failed to create BlueBrick logger: {e}");
-                let _ = msgbox::create("Error creating BlueBrick logger", &msg, msgbox::IconType::Error);
-                panic!("{msg}");
-            }
+        Self {
+            overlay: OverlaySink::new(),
+            log_scroll_changed: false,
+            session: SessionInfo::default(),
+            sink_names: None,
+            server_address: None,
+            file_limits: FileLimits::default(),
+            sinks: None,
         }
+    }
 
-        // needed for colored to use truecolor
-        // should be safe since no other threads should be running during BlueBrick startup
-        unsafe { env::set_var("COLORTERM", "truecolor") };
+    pub fn set_config(&mut self, config: Config) {
+        self.session.platform = Some(format!("{:?}", config.platform));
+        self.session.renderer = Some(format!("{:?}", config.renderer));
+        self.sink_names = config.log_sinks();
+        self.server_address = config.log_server();
+        self.file_limits = FileLimits::from_config(&config);
+    }
 
-        Self {
-            log_items: Vec::new(),
-            log_scroll_changed: false,
-            file: match File::create("bluebrick/log.txt") {
-                Ok(file) => file,
-                Err(e) => {
-                    let msg = format!("Failed to create or open BlueBrick log file: {e}");
-                    let _ = msgbox::create("Error creating BlueBrick logger", &msg, msgbox::IconType::Error);
-                    panic!("{msg}");
-                }
+    pub fn set_loaded_bricks(&mut self, bricks: Vec<String>) {
+        let mut failed = Vec::new();
+        for sink in self.sinks.iter_mut().flatten() {
+            if let Err(e) = sink.bricks_loaded(&bricks) {
+                failed.push(format!("Log sink {} could not record the loaded bricks: {e}", sink.name()));
             }
         }
+        self.session.bricks = Some(bricks);
+
+        for msg in failed {
+            self.log_with_severity(&msg, Severity::Warning);
+        }
     }
 
     pub fn log_with_severity(&mut self, msg: &str, severity: Severity) {
-        self.log_impl("Loader", "BlueBrick", Some(WebColor::DeepSkyBlue), &msg, severity);
+        if !levels::loader_enabled(severity) {
+            return;
+        }
+        self.log_impl("Loader", "BlueBrick", Some(WebColor::DeepSkyBlue.into()), msg, severity);
     }
 
-    fn log_impl<C: Into<Color>>(&mut self, kind: &str, name: &str, name_color: Option<C>, msg: &str, severity: Severity) {
+    /// Returns the id of the first overlay record written for the message
+    pub(crate) fn log_impl(&mut self, kind: &'static str, name: &str, name_color: Option<Color>, msg: &str, severity: Severity) -> u64 {
         let msg = msg.replace("\r", "");
-        let msg = Self::format_msg(kind, name, name_color, &msg, severity);
+        let record = LogRecord::new(kind, name, name_color, &msg, severity);
+        let id = self.overlay.records.next_id();
 
-        print!("{msg}");
-        self.log_to_file(&msg);
-        self.log_to_overlay(&msg);
+        if self.sinks.is_none() {
+            self.open_sinks();
+        }
+        self.log_to_sinks(&record);
 
         self.log_scroll_changed = true;
+        id
     }
 
-    fn format_msg<C: Into<Color>>(kind: &str, name: &str, name_color: Option<C>, msg: &str, severity: Severity) -> String {
-        // colors
-        let time_color = match severity {
-            Severity::Debug => Some(WebColor::Gray), // the web color "gray" is darker than "dark gray" ...
-            Severity::Warning => Some(WebColor::Goldenrod),
-            Severity::Error => Some(WebColor::Firebrick),
-            _ => Some(WebColor::Gray),
-        };
-        let kind_color = match severity {
-            Severity::Debug => Some(WebColor::DarkGray),
-            Severity::Warning => Some(WebColor::Yellow),
-            Severity::Error => Some(WebColor::Red),
-            _ => None,
-        };
-        let text_color = match severity {
-            Severity::Debug => Some(WebColor::DarkGray),
-            Severity::Warning => Some(WebColor::Yellow),
-            Severity::Error => Some(WebColor::Red),
-            _ => None,
-        };
-        
-        let time = chrono::Local::now();
-        let time = format!("{}", time.format("%T"));
-        let time = Self::apply_color(&time, time_color);
+    /// Opens the configured sinks, any that fail are reported through the rest
+    fn open_sinks(&mut self) {
+        let (kinds, unknown) = SinkKind::from_names(self.sink_names.as_deref());
+        let mut sinks = Vec::new();
+        let mut problems = unknown.into_iter().map(|name| format!("Unknown log sink {name} was ignored")).collect::<Vec<_>>();
 
-        let kind = Self::apply_color(&kind, kind_color);
+        for kind in kinds {
+            match kind.open(&self.session, self.file_limits, self.server_address.as_deref()) {
+                Ok(sink) => sinks.push(sink),
+                Err(e) => problems.push(format!("Log sink {kind:?} is unavailable, continuing without it: {e}")),
+            }
+        }
+        self.sinks = Some(sinks);
 
-        let name = Self::apply_color(name, name_color);
+        for problem in problems {
+            self.log_to_sinks(&LogRecord::new("Loader", "BlueBrick", Some(WebColor::DeepSkyBlue.into()), &problem, Severity::Warning));
+        }
+    }
 
-        let msg = Self::apply_color(msg, text_color);
+    /// Writes to every sink, dropping sinks that fail and reporting it through the rest
+    fn log_to_sinks(&mut self, record: &LogRecord) {
+        let _ = self.overlay.write(record);
 
-        format!("{time} [{kind}] [{name}] {msg}\n")
-    }
+        let Some(sinks) = &mut self.sinks else {
+            return;
+        };
 
-    fn apply_color<C: Into<Color>>(string: &str, color: Option<C>) -> ColoredString {
-        match color {
-            Some(color) => string.color(color),
-            None => string.normal(),
+        let mut problems = Vec::new();
+        sinks.retain_mut(|sink| match sink.write(record) {
+            Ok(()) => true,
+            Err(e) => {
+                problems.push(format!("Log sink {} failed and was disabled: {e}", sink.name()));
+                false
+            }
+        });
+
+        for problem in problems {
+            self.log_to_sinks(&LogRecord::new("Loader", "BlueBrick", Some(WebColor::DeepSkyBlue.into()), &problem, Severity::Warning));
         }
     }
 
-    fn log_to_file(&mut self, msg: &str) {
-        static RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\x1b.*?m").unwrap());
-        let msg = RE.replace_all(msg, "");
-        let _ = self.file.write_all(msg.as_bytes());
-    }
+    pub fn draw_logs(&mut self, ui: &Ui, filter: &mut LogFilter) {
+        filter.draw_toolbar(ui, self.overlay.names.iter());
+        filter.refresh(&self.overlay.records);
 
-    fn log_to_overlay(&mut self, msg: &str) {
-        static RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\x1b\[(?P<color>(?:\d{1,3};)*\d{1,3})m|(?<newline>\n)|(?<text>[^\n\x1b]+)").unwrap());
-        let log_items = RE.captures_iter(msg);
+        ui.child_window("LogLines").horizontal_scrollbar(true).build(|| {
+            let _spacing = ui.push_style_var(StyleVar::ItemSpacing([0.0, 0.2]));
 
-        for log_item in log_items {
-            if let Some(text) = log_item.name("text").map(|m| m.as_str()) {
-                self.log_items.push(LogItem::Text(String::from(text)));
+            if let Some(id) = filter.scroll_to.take()
+                && let Ok(row) = filter.visible.binary_search(&id)
+            {
+                ui.set_scroll_y(row as f32 * ui.text_line_height_with_spacing());
             }
-            else if let Some(_) = log_item.name("newline") {
-                self.log_items.push(LogItem::NewLine);
-            }
-            else if let Some(color_txt) = log_item.name("color").map(|m| m.as_str()) {
-                if let Some(color) = Self::parse_color_txt(color_txt) {
-                    self.log_items.push(color);
+
+            for row in ListClipper::new(filter.visible.len() as i32).begin(ui).iter() {
+                let id = filter.visible[row as usize];
+                let Some(record) = self.overlay.records.get(id) else {
+                    continue;
+                };
+
+                let segments = record.segments();
+                let text = segments.iter().map(|(text, _)| text.as_str()).collect::<String>();
+                let matches = filter.find_matches(&text);
+                ui.group(|| Self::draw_record(ui, &segments, &matches));
+
+                if filter.selected.contains(&id) {
+                    ui.get_window_draw_list()
+                        .add_rect(ui.item_rect_min(), [ui.item_rect_min()[0] + ui.window_size()[0], ui.item_rect_max()[1]], [0.3, 0.5, 1.0, 0.25])
+                        .filled(true)
+                        .build();
                 }
-            }
-        }
-    }
 
-    fn parse_color_txt(color_txt: &str) -> Option<LogItem> {
-        let mut color = None;
-
-        let nums = color_txt.split(';').map(|n| n.parse::<u8>().unwrap_or_default()).collect::<Vec<_>>();
-
-        let mut i = 0;
-        while i < nums.len() {
-            let mut matched = true;
-
-            match nums[i] {
-                0 => color = Some(LogItem::StyleReset),
-                30 => color = Some(LogItem::Color(Color::Black)),
-                31 => color = Some(LogItem::Color(Color::Red)),
-                32 => color = Some(LogItem::Color(Color::Green)),
-                33 => color = Some(LogItem::Color(Color::Yellow)),
-                34 => color = Some(LogItem::Color(Color::Blue)),
-                35 => color = Some(LogItem::Color(Color::Magenta)),
-                36 => color = Some(LogItem::Color(Color::Cyan)),
-                37 => color = Some(LogItem::Color(Color::White)),
-                90 => color = Some(LogItem::Color(Color::BrightBlack)),
-                91 => color = Some(LogItem::Color(Color::BrightRed)),
-                92 => color = Some(LogItem::Color(Color::BrightGreen)),
-                93 => color = Some(LogItem::Color(Color::BrightYellow)),
-                94 => color = Some(LogItem::Color(Color::BrightBlue)),
-                95 => color = Some(LogItem::Color(Color::BrightMagenta)),
-                96 => color = Some(LogItem::Color(Color::BrightCyan)),
-                97 => color = Some(LogItem::Color(Color::BrightWhite)),
-                _ => matched = false,
-            }
-            if matched {
-                i += 1;
-                continue;
+                if ui.is_item_clicked() {
+                    let io = ui.io();
+                    filter.click(id, io.key_ctrl, io.key_shift);
+                } else if ui.is_item_clicked_with_button(MouseButton::Right) {
+                    if !filter.selected.contains(&id) {
+                        filter.click(id, false, false);
+                    }
+                    ui.open_popup("LogContext");
+                }
             }
-            matched = true;
 
-            if i + 4 < nums.len() {
-                match nums[i..i + 5] {
-                    [38, 2, r, g, b] => color = Some(LogItem::Color(Color::TrueColor { r, g, b })),
-                    _ => matched = false,
+            ui.popup("LogContext", || {
+                if ui.menu_item_config("Copy").shortcut("Ctrl + C").build() {
+                    Self::copy_records(&self.overlay.records, ui, filter.selected.iter().copied());
                 }
-                if matched {
-                    i += 5;
-                    continue;
+                if ui.menu_item("Copy all shown") {
+                    Self::copy_records(&self.overlay.records, ui, filter.visible.iter().copied());
+                }
+                if ui.menu_item("Clear selection") {
+                    filter.selected.clear();
                 }
+            });
+
+            if ui.is_window_focused() && ui.io().key_ctrl && ui.is_key_pressed(Key::C) {
+                Self::copy_records(&self.overlay.records, ui, filter.selected.iter().copied());
             }
 
-            i += 1;
-        }
+            // scroll to end, including logic for the scrollbar covering part of the window
+            if filter.auto_scroll && self.log_scroll_changed && (ui.scroll_y() + ui.clone_style().scrollbar_size) >= ui.scroll_max_y() {
+                ui.set_scroll_here_y_with_ratio(1.0);
+                self.log_scroll_changed = false;
+            }
+        });
+    }
 
-        color
+    fn draw_record(ui: &Ui, segments: &[(String, Option<Color>)], matches: &[Range<usize>]) {
+        let mut offset = 0;
+        for (text, color) in segments {
+            let _color = color.map(|color| ui.push_style_color(StyleColor::Text, color.to_imgui()));
+            Self::draw_highlighted(ui, text, offset, matches);
+            offset += text.len();
+        }
+        ui.new_line();
     }
 
-    pub fn draw_logs(&mut self, ui: &Ui) {
-        let _spacing = ui.push_style_var(StyleVar::ItemSpacing([0.0, 0.2]));
+    /// Draws a piece of a line starting at `offset` in it, with a background behind the parts that are in `matches`
+    fn draw_highlighted(ui: &Ui, msg: &str, offset: usize, matches: &[Range<usize>]) {
+        let end = offset + msg.len();
+        let mut pos = offset;
 
-        let mut _current_color = None;
-        for item in &self.log_items {
-            match item {
-                LogItem::Text(msg) => {
-                    ui.text(msg);
-                    ui.same_line();
-                }
-                LogItem::NewLine => ui.text(""), // better than new_line() because it can stack multiple new lines
-                LogItem::Color(color) => _current_color = Some(ui.push_style_color(StyleColor::Text, color_to_f32_4(*color))),
-                LogItem::StyleReset => _current_color = None,
+        for range in matches.iter().filter(|range| range.start < end && range.end > offset) {
+            let start = range.start.max(offset);
+            if start > pos {
+                ui.text(&msg[pos - offset..start - offset]);
+                ui.same_line();
             }
+
+            let stop = range.end.min(end);
+            let piece = &msg[start - offset..stop - offset];
+            let min = ui.cursor_screen_pos();
+            let size = ui.calc_text_size(piece);
+            ui.get_window_draw_list()
+                .add_rect(min, [min[0] + size[0], min[1] + size[1]], [1.0, 0.85, 0.0, 0.45])
+                .filled(true)
+                .build();
+            ui.text(piece);
+            ui.same_line();
+
+            pos = stop;
         }
 
-        // scroll to end, including logic for the scrollbar covering part of the window
-        if self.log_scroll_changed && (ui.scroll_y() + ui.clone_style().scrollbar_size) >= ui.scroll_max_y() {
-            ui.set_scroll_here_y_with_ratio(1.0);
-            self.log_scroll_changed = false;
+        if pos < end {
+            ui.text(&msg[pos - offset..]);
+            ui.same_line();
         }
     }
 
-    pub fn instance() -> MutexGuard<'static, Self> {
+    fn copy_records(records: &LogBuffer, ui: &Ui, ids: impl Iterator<Item = u64>) {
+        let text = ids.filter_map(|id| records.get(id)).map(|record| record.to_plain() + "\n").collect::<String>();
+        if !text.is_empty() {
+            ui.set_clipboard_text(text);
+        }
+    }
+
+    fn mutex() -> &'static Mutex<Self> {
         static MAIN_LOGGER: OnceLock<Mutex<MainLogger>> = OnceLock::new();
-        MAIN_LOGGER.get_or_init(|| Mutex::new(MainLogger::new())).lock().unwrap()
+        MAIN_LOGGER.get_or_init(|| Mutex::new(MainLogger::new()))
     }
-}
 
-fn u8_to_f32(byte: u8) -> f32 {
-    byte as f32 / u8::MAX as f32
-}
+    pub fn instance() -> MutexGuard<'static, Self> {
+        Self::mutex().lock().unwrap()
+    }
 
-fn color_to_f32_4(color: Color) -> [f32; 4] {
-    use Color::*;
-    match color {
-        Black => [0.0, 0.0, 0.0, 1.0 ],
-        Red => [205.0, 0.0, 0.0, 1.0 ],
-        Green => [0.0, 205.0, 0.0, 1.0 ],
-        Yellow => [205.0, 205.0, 0.0, 1.0 ],
-        Blue => [0.0, 0.0, 238.0, 1.0 ],
-        Magenta => [205.0, 0.0, 205.0, 1.0 ],
-        Cyan => [0.0, 205.0, 205.0, 1.0 ],
-        White => [229.0, 229.0, 229.0, 1.0 ],
-        BrightBlack => [127.0, 127.0, 127.0, 1.0 ],
-        BrightRed => [255.0, 0.0, 0.0, 1.0 ],
-        BrightGreen => [0.0, 255.0, 0.0, 1.0 ],
-        BrightYellow => [255.0, 255.0, 0.0, 1.0 ],
-        BrightBlue => [92.0, 92.0, 255.0, 1.0 ],
-        BrightMagenta => [255.0, 0.0, 255.0, 1.0 ],
-        BrightCyan => [0.0, 255.0, 255.0, 1.0 ],
-        BrightWhite => [255.0, 255.0, 255.0, 1.0 ],
-        TrueColor { r, g, b } => [u8_to_f32(r), u8_to_f32(g), u8_to_f32(b), 1.0],
+    /// Doesn't wait for the lock or panic if it is poisoned, for use while crashing
+    pub fn try_instance() -> Option<MutexGuard<'static, Self>> {
+        match Self::mutex().try_lock() {
+            Ok(logger) => Some(logger),
+            Err(TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+            Err(TryLockError::WouldBlock) => None,
+        }
     }
 }
 
-// TODO: implement name color
-fn log_impl(kind: &str, name: *const c_char, msg: *const c_char, severity: Severity) {
+fn log_impl(kind: &'static str, name: *const c_char, name_color: Option<Color>, msg: *const c_char, severity: Severity) {
     let name = unsafe { CStr::from_ptr(name) };
     let name = String::from_utf8_lossy(name.to_bytes()).to_string();
     let msg = unsafe { CStr::from_ptr(msg) };
     let msg = String::from_utf8_lossy(msg.to_bytes()).to_string();
-    MainLogger::instance().log_impl::<Color>(kind, &name, None, &msg, severity);
+    MainLogger::instance().log_impl(kind, &name, name_color, &msg, severity);
+}
+
+pub(crate) extern "C" fn log_library_impl(name: *const c_char, name_color: Option<&Rgb>, msg: *const c_char, severity: Severity) {
+    log_impl("Library", name, name_color.copied().map(Color::from), msg, severity);
+}
+
+pub(crate) extern "C" fn log_mod_impl(name: *const c_char, name_color: Option<&Rgb>, msg: *const c_char, severity: Severity) {
+    log_impl("Mod", name, name_color.copied().map(Color::from), msg, severity);
 }
 
-#[unsafe(no_mangle)]
-extern "C" fn log_library_impl(name: *const c_char, msg: *const c_char, severity: Severity) {
-    log_impl("Library", name, msg, severity);
+pub(crate) extern "C" fn log_library_color_impl(name: *const c_char, name_color: Option<&Color>, msg: *const c_char, severity: Severity) {
+    log_impl("Library", name, name_color.copied(), msg, severity);
 }
 
-#[unsafe(no_mangle)]
-extern "C" fn log_mod_impl(name: *const c_char, msg: *const c_char, severity: Severity) {
-    log_impl("Mod", name, msg, severity);
+pub(crate) extern "C" fn log_mod_color_impl(name: *const c_char, name_color: Option<&Color>, msg: *const c_char, severity: Severity) {
+    log_impl("Mod", name, name_color.copied(), msg, severity);
 }
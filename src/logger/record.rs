@@ -0,0 +1,147 @@
+use std::collections::VecDeque;
+
+use bluebrick::{color::Color, logger::Severity, web_colors::WebColor};
+use chrono::{DateTime, Local};
+
+#[derive(Clone)]
+pub(crate) struct LogRecord {
+    pub id: u64,
+    pub time: DateTime<Local>,
+    pub severity: Severity,
+    pub kind: &'static str,
+    pub name: String,
+    pub message: String,
+    /// Set on the lines after the first of a multi-line message, which are drawn without the header
+    pub continuation: bool,
+    pub time_color: Option<Color>,
+    pub kind_color: Option<Color>,
+    pub name_color: Option<Color>,
+    pub text_color: Option<Color>,
+}
+
+impl LogRecord {
+    pub fn new(kind: &'static str, name: &str, name_color: Option<Color>, message: &str, severity: Severity) -> Self {
+        let time_color = match severity {
+            Severity::Trace => Some(WebColor::DimGray),
+            Severity::Debug => Some(WebColor::Gray), // the web color "gray" is darker than "dark gray" ...
+            Severity::Warning => Some(WebColor::Goldenrod),
+            Severity::Error => Some(WebColor::Firebrick),
+            _ => Some(WebColor::Gray),
+        };
+        let kind_color = match severity {
+            Severity::Trace => Some(WebColor::Gray),
+            Severity::Debug => Some(WebColor::DarkGray),
+            Severity::Warning => Some(WebColor::Yellow),
+            Severity::Error => Some(WebColor::Red),
+            _ => None,
+        };
+        let text_color = match severity {
+            Severity::Trace => Some(WebColor::Gray),
+            Severity::Debug => Some(WebColor::DarkGray),
+            Severity::Warning => Some(WebColor::Yellow),
+            Severity::Error => Some(WebColor::Red),
+            _ => None,
+        };
+
+        Self {
+            id: 0,
+            time: Local::now(),
+            severity,
+            kind,
+            name: String::from(name),
+            message: String::from(message),
+            continuation: false,
+            time_color: time_color.map(Color::from),
+            kind_color: kind_color.map(Color::from),
+            name_color,
+            text_color: text_color.map(Color::from),
+        }
+    }
+
+    /// The pieces of the formatted line and their colors, in order
+    pub fn segments(&self) -> Vec<(String, Option<Color>)> {
+        if self.continuation {
+            return vec![
+                (String::from("    "), None),
+                (self.message.clone(), self.text_color),
+            ];
+        }
+
+        vec![
+            (format!("{}", self.time.format("%T")), self.time_color),
+            (String::from(" ["), None),
+            (String::from(self.kind), self.kind_color),
+            (String::from("] ["), None),
+            (self.name.clone(), self.name_color),
+            (String::from("] "), None),
+            (self.message.clone(), self.text_color),
+        ]
+    }
+
+    pub fn to_plain(&self) -> String {
+        self.segments().into_iter().map(|(text, _)| text).collect()
+    }
+
+    pub fn to_ansi(&self) -> String {
+        self.segments().into_iter().map(|(text, color)| match color {
+            Some(color) => color.paint(&text),
+            None => text,
+        }).collect()
+    }
+
+    /// Splits a record into one record per line of its message
+    pub fn lines(&self) -> Vec<LogRecord> {
+        self.message.split('\n').enumerate().map(|(i, line)| LogRecord {
+            message: String::from(line),
+            continuation: i > 0,
+            ..self.clone()
+        }).collect()
+    }
+}
+
+/// Keeps the most recent records, dropping the oldest once full
+pub(crate) struct LogBuffer {
+    records: VecDeque<LogRecord>,
+    capacity: usize,
+    next_id: u64,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            records: VecDeque::with_capacity(capacity),
+            capacity,
+            next_id: 0,
+        }
+    }
+
+    pub fn push(&mut self, mut record: LogRecord) {
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+
+        record.id = self.next_id;
+        self.next_id += 1;
+        self.records.push_back(record);
+    }
+
+    /// The id of the oldest record still kept
+    pub fn first_id(&self) -> u64 {
+        self.next_id - self.records.len() as u64
+    }
+
+    /// The id the next pushed record will get
+    pub fn next_id(&self) -> u64 {
+        self.next_id
+    }
+
+    pub fn get(&self, id: u64) -> Option<&LogRecord> {
+        let index = id.checked_sub(self.first_id())?;
+        self.records.get(index as usize)
+    }
+
+    pub fn iter_from(&self, id: u64) -> impl Iterator<Item = &LogRecord> {
+        let index = id.saturating_sub(self.first_id()) as usize;
+        self.records.range(index.min(self.records.len())..)
+    }
+}
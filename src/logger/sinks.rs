@@ -0,0 +1,79 @@
+mod console;
+mod debugger;
+mod json;
+mod overlay;
+mod server;
+mod system;
+
+use std::io;
+
+use super::files::{FileLimits, LogFiles, SessionInfo};
+use super::record::LogRecord;
+
+pub(crate) use overlay::OverlaySink;
+
+/// An output the logger writes every record to, each one can fail without affecting the others
+pub(crate) trait LogSink: Send {
+    fn name(&self) -> &'static str;
+    fn write(&mut self, record: &LogRecord) -> io::Result<()>;
+
+    /// Called once the bricks are loaded, for sinks that record the session's bricks
+    fn bricks_loaded(&mut self, _bricks: &[String]) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum SinkKind {
+    Console,
+    File,
+    Json,
+    System,
+    Debugger,
+    Server,
+}
+
+impl SinkKind {
+    const DEFAULT: [SinkKind; 2] = [SinkKind::Console, SinkKind::File];
+
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "console" => Some(Self::Console),
+            "file" => Some(Self::File),
+            "json" => Some(Self::Json),
+            "system" | "syslog" | "journald" => Some(Self::System),
+            "debugger" => Some(Self::Debugger),
+            "server" => Some(Self::Server),
+            _ => None,
+        }
+    }
+
+    /// Reads the config's comma separated list of sinks, the default ones if it has none, returning the unknown names as well
+    pub fn from_names(names: Option<&str>) -> (Vec<Self>, Vec<String>) {
+        let Some(names) = names else {
+            return (Vec::from(Self::DEFAULT), Vec::new());
+        };
+
+        let mut kinds = Vec::new();
+        let mut unknown = Vec::new();
+        for name in names.split(',').filter(|name| !name.trim().is_empty()) {
+            match Self::parse(name) {
+                Some(kind) if !kinds.contains(&kind) => kinds.push(kind),
+                Some(_) => {}
+                None => unknown.push(String::from(name.trim())),
+            }
+        }
+        (kinds, unknown)
+    }
+
+    pub fn open(self, session: &SessionInfo, file_limits: FileLimits, server_address: Option<&str>) -> io::Result<Box<dyn LogSink>> {
+        Ok(match self {
+            SinkKind::Console => Box::new(console::ConsoleSink::open()?),
+            SinkKind::File => Box::new(LogFiles::open(session, file_limits)?),
+            SinkKind::Json => Box::new(json::JsonSink::open()?),
+            SinkKind::System => Box::new(system::SystemSink::open()?),
+            SinkKind::Debugger => Box::new(debugger::DebuggerSink::open()?),
+            SinkKind::Server => Box::new(server::ServerSink::open(server_address)?),
+        })
+    }
+}
@@ -0,0 +1,37 @@
+use std::io::{self, Write};
+
+use super::LogSink;
+use crate::logger::record::LogRecord;
+
+pub(crate) struct ConsoleSink {
+    colored: bool,
+}
+
+impl ConsoleSink {
+    pub fn open() -> io::Result<Self> {
+        #[cfg(windows)] {
+            // the game may already have a console, in which case colors might not be supported
+            if let Err(e) = crate::logger::windows::init_terminal() {
+                return match crate::logger::windows::attach_terminal() {
+                    Ok(colored) => Ok(Self { colored }),
+                    Err(_) => Err(io::Error::other(e)),
+                };
+            }
+        }
+
+        Ok(Self { colored: true })
+    }
+}
+
+impl LogSink for ConsoleSink {
+    fn name(&self) -> &'static str {
+        "console"
+    }
+
+    fn write(&mut self, record: &LogRecord) -> io::Result<()> {
+        let line = if self.colored { record.to_ansi() } else { record.to_plain() };
+        let mut stdout = io::stdout().lock();
+        writeln!(stdout, "{line}")?;
+        stdout.flush()
+    }
+}
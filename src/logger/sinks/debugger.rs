@@ -0,0 +1,37 @@
+use std::io;
+
+use super::LogSink;
+use crate::logger::record::LogRecord;
+
+/// Sends records to an attached debugger's output window
+pub(crate) struct DebuggerSink;
+
+impl DebuggerSink {
+    pub fn open() -> io::Result<Self> {
+        if cfg!(windows) {
+            Ok(Self)
+        } else {
+            Err(io::Error::new(io::ErrorKind::Unsupported, "the debugger sink is only supported on windows"))
+        }
+    }
+}
+
+impl LogSink for DebuggerSink {
+    fn name(&self) -> &'static str {
+        "debugger"
+    }
+
+    #[cfg(windows)]
+    fn write(&mut self, record: &LogRecord) -> io::Result<()> {
+        use windows::{Win32::System::Diagnostics::Debug::OutputDebugStringW, core::HSTRING};
+
+        let line = HSTRING::from(format!("{}\n", record.to_plain()));
+        unsafe { OutputDebugStringW(&line) };
+        Ok(())
+    }
+
+    #[cfg(not(windows))]
+    fn write(&mut self, _record: &LogRecord) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use std::{fs::{self, File}, io::{self, BufWriter, Write}};
+
+use serde_json::{Value, json};
+
+use super::LogSink;
+use crate::logger::record::LogRecord;
+use crate::paths;
+
+const JSON_NAME: &str = "latest.jsonl";
+
+/// Writes one JSON object per line, for tools that read the logs
+pub(crate) struct JsonSink {
+    file: BufWriter<File>,
+}
+
+impl JsonSink {
+    pub fn open() -> io::Result<Self> {
+        let folder = paths::log_dir();
+        fs::create_dir_all(folder)?;
+        Ok(Self {
+            file: BufWriter::new(File::create(folder.join(JSON_NAME))?),
+        })
+    }
+}
+
+/// The structured form of a record, shared by the sinks that write JSON
+pub(crate) fn record_json(record: &LogRecord) -> Value {
+    json!({
+        "time": record.time.to_rfc3339(),
+        "severity": record.severity.name(),
+        "kind": record.kind,
+        "name": record.name,
+        "name_color": record.name_color.map(|color| color.to_hex()),
+        "message": record.message,
+    })
+}
+
+impl LogSink for JsonSink {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn write(&mut self, record: &LogRecord) -> io::Result<()> {
+        writeln!(self.file, "{}", record_json(record))?;
+        self.file.flush()
+    }
+}
@@ -0,0 +1,39 @@
+use std::{collections::BTreeSet, io};
+
+use super::LogSink;
+use crate::logger::record::{LogBuffer, LogRecord};
+
+/// How many lines the overlay keeps before dropping the oldest
+const MAX_OVERLAY_RECORDS: usize = 50_000;
+
+/// Keeps recent records in memory for the log window
+pub(crate) struct OverlaySink {
+    pub records: LogBuffer,
+    pub names: BTreeSet<String>,
+}
+
+impl OverlaySink {
+    pub fn new() -> Self {
+        Self {
+            records: LogBuffer::new(MAX_OVERLAY_RECORDS),
+            names: BTreeSet::new(),
+        }
+    }
+}
+
+impl LogSink for OverlaySink {
+    fn name(&self) -> &'static str {
+        "overlay"
+    }
+
+    fn write(&mut self, record: &LogRecord) -> io::Result<()> {
+        if !self.names.contains(&record.name) {
+            self.names.insert(record.name.clone());
+        }
+
+        for line in record.lines() {
+            self.records.push(line);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+use std::{collections::VecDeque, io::{self, Write}, net::TcpListener, sync::mpsc::{self, Sender}, thread, time::Duration};
+
+use serde_json::{Value, json};
+
+use super::LogSink;
+use super::json::record_json;
+use crate::logger::record::LogRecord;
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:7345";
+const HISTORY_SIZE: usize = 1000;
+/// A viewer that stops reading is dropped after this, rather than holding up the game
+const WRITE_TIMEOUT: Duration = Duration::from_millis(250);
+
+#[derive(Default)]
+struct Clients {
+    /// The most recent records, replayed to viewers when they connect
+    history: VecDeque<Value>,
+    streams: Vec<Box<dyn Write + Send>>,
+}
+
+impl Clients {
+    fn send(stream: &mut dyn Write, record: &Value, replayed: bool) -> io::Result<()> {
+        let mut record = record.clone();
+        record["replayed"] = json!(replayed);
+        writeln!(stream, "{record}")?;
+        stream.flush()
+    }
+
+    fn connect(&mut self, mut stream: Box<dyn Write + Send>) {
+        if self.history.iter().all(|record| Self::send(&mut stream, record, true).is_ok()) {
+            self.streams.push(stream);
+        }
+    }
+
+    fn broadcast(&mut self, record: Value) {
+        self.streams.retain_mut(|stream| Self::send(stream, &record, false).is_ok());
+
+        if self.history.len() == HISTORY_SIZE {
+            self.history.pop_front();
+        }
+        self.history.push_back(record);
+    }
+}
+
+/// What the sink and the listener hand to the thread that writes to viewers
+enum Message {
+    Record(Value),
+    Connect(Box<dyn Write + Send>),
+}
+
+/// Streams records as JSON lines to viewers connected over TCP or a Unix socket,
+/// set with `log_server` in the config as `host:port` or `unix:/path/to/socket`
+pub(crate) struct ServerSink {
+    tx: Sender<Message>,
+}
+
+impl ServerSink {
+    pub fn open(address: Option<&str>) -> io::Result<Self> {
+        let address = address.unwrap_or(DEFAULT_ADDRESS);
+        let (tx, rx) = mpsc::channel();
+
+        match address.strip_prefix("unix:") {
+            Some(path) => Self::listen_unix(path, tx.clone())?,
+            None => Self::listen_tcp(address, tx.clone())?,
+        }
+
+        // viewers are only written to here, so a slow one never holds up whoever is logging
+        thread::spawn(move || {
+            let mut clients = Clients::default();
+            while let Ok(message) = rx.recv() {
+                match message {
+                    Message::Record(record) => clients.broadcast(record),
+                    Message::Connect(stream) => clients.connect(stream),
+                }
+            }
+        });
+
+        Ok(Self { tx })
+    }
+
+    fn listen_tcp(address: &str, tx: Sender<Message>) -> io::Result<()> {
+        let listener = TcpListener::bind(address)?;
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+                let _ = stream.set_nodelay(true);
+                if tx.send(Message::Connect(Box::new(stream))).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn listen_unix(path: &str, tx: Sender<Message>) -> io::Result<()> {
+        use std::{fs, os::unix::{fs::FileTypeExt, net::UnixListener}};
+
+        // a socket left behind by a previous session would stop the bind, anything else at the path is left alone
+        if fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_socket()) {
+            let _ = fs::remove_file(path);
+        }
+        let listener = UnixListener::bind(path)?;
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+                if tx.send(Message::Connect(Box::new(stream))).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn listen_unix(_path: &str, _tx: Sender<Message>) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "unix sockets are only supported on unix"))
+    }
+}
+
+impl LogSink for ServerSink {
+    fn name(&self) -> &'static str {
+        "server"
+    }
+
+    fn write(&mut self, record: &LogRecord) -> io::Result<()> {
+        self.tx.send(Message::Record(record_json(record))).map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "the log server stopped"))
+    }
+}
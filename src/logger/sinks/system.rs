@@ -0,0 +1,67 @@
+use std::io;
+
+use super::LogSink;
+use crate::logger::record::LogRecord;
+
+/// Sends records to syslog, which journald picks up on systemd systems
+#[cfg(unix)]
+pub(crate) struct SystemSink;
+
+#[cfg(unix)]
+impl SystemSink {
+    pub fn open() -> io::Result<Self> {
+        static IDENT: &std::ffi::CStr = c"bluebrick";
+        unsafe { libc::openlog(IDENT.as_ptr(), libc::LOG_PID, libc::LOG_USER) };
+        Ok(Self)
+    }
+}
+
+#[cfg(unix)]
+impl LogSink for SystemSink {
+    fn name(&self) -> &'static str {
+        "system"
+    }
+
+    fn write(&mut self, record: &LogRecord) -> io::Result<()> {
+        use bluebrick::logger::Severity;
+
+        let priority = match record.severity {
+            Severity::Trace | Severity::Debug => libc::LOG_DEBUG,
+            Severity::Info => libc::LOG_INFO,
+            Severity::Warning => libc::LOG_WARNING,
+            Severity::Error => libc::LOG_ERR,
+        };
+        let msg = format!("[{}] [{}] {}", record.kind, record.name, record.message).replace('\0', "");
+        let msg = std::ffi::CString::new(msg).map_err(io::Error::other)?;
+        unsafe { libc::syslog(priority, c"%s".as_ptr(), msg.as_ptr()) };
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+impl Drop for SystemSink {
+    fn drop(&mut self) {
+        unsafe { libc::closelog() };
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) struct SystemSink;
+
+#[cfg(not(unix))]
+impl SystemSink {
+    pub fn open() -> io::Result<Self> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "the system log sink is only supported on unix"))
+    }
+}
+
+#[cfg(not(unix))]
+impl LogSink for SystemSink {
+    fn name(&self) -> &'static str {
+        "system"
+    }
+
+    fn write(&mut self, _record: &LogRecord) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -26,3 +26,15 @@ pub fn init_terminal() -> Result<()> {
         Ok(())
     }
 }
+
+/// Uses a console the game already has, returns whether it supports colors
+pub fn attach_terminal() -> Result<bool> {
+    unsafe {
+        let output_handle = Console::GetStdHandle(Console::STD_OUTPUT_HANDLE)?;
+        if output_handle.is_invalid() || output_handle.0.is_null() {
+            return Err(Error::from_win32());
+        }
+
+        Ok(enable_color().is_ok())
+    }
+}
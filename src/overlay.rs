@@ -1,50 +1,43 @@
+pub mod notifications;
 mod platforms;
 mod renderers;
 
-use std::{error::Error, path::PathBuf, sync::mpsc::{self, Sender}};
+use std::{error::Error, time::Instant};
 
 use bluebrick_proxy::Config;
 use bluebrick::imgui::{self, Condition, ConfigFlags, FontConfig, FontGlyphRanges, FontSource, Key, Ui};
 
-use crate::BBEvent;
-use crate::logger::{MainLogger, main_log};
-use crate::overlay::renderers::{RendererEvent, SomeRenderer, SomeRendererHandle};
-use crate::overlay::platforms::{PlatformEvent, SomePlatform, SomePlatformHandle};
+use crate::paths;
+use crate::diagnostics::{Diagnostics, FrameTimes};
+use crate::logger::{MainLogger, filter::LogFilter, levels::LogLevels, main_log};
+use crate::overlay::notifications::Notifications;
+use crate::overlay::platforms::{Platform, SomePlatform, SomePlatformHandle};
 use crate::subbrick::SubBrickManager;
 
-pub enum OverlayEvent {
-    Draw(Sender<()>),
-    PostDraw(Sender<()>),
-    Platform(PlatformEvent),
-    Renderer(RendererEvent),
-}
-
-impl Into<BBEvent> for OverlayEvent {
-    fn into(self) -> BBEvent {
-        BBEvent::Overlay(self)
-    }
-}
-
 pub struct Overlay {
     imgui: imgui::Context,
     platform: SomePlatform,
-    renderer: SomeRenderer,
     show_hide_key: Key,
     is_showing: bool,
     show_demo_window: bool,
     show_logs: bool,
+    show_log_levels: bool,
     show_bricks: bool,
+    show_diagnostics: bool,
+    log_filter: LogFilter,
+    frame_times: FrameTimes,
 }
 
 impl Overlay {
-    pub fn new(config: Config) -> Result<Self, Box<dyn Error>> {
+    /// Opens the diagnostics window right away in safe mode
+    pub fn new(config: Config, safe_mode: bool) -> Result<Self, Box<dyn Error>> {
         let platform = SomePlatform::new(config)?;
-        let renderer = SomeRenderer::new(config)?;
+        renderers::arm(config)?;
 
         let mut imgui = imgui::Context::create();
         imgui.style_mut().use_dark_colors();
 
-        imgui.set_ini_filename(Some(PathBuf::from("bluebrick/imgui.ini")));
+        imgui.set_ini_filename(Some(paths::config_dir().join("imgui.ini")));
 
         imgui.io_mut().config_flags |= ConfigFlags::DOCKING_ENABLE;
 
@@ -53,12 +46,15 @@ impl Overlay {
         Ok(Self {
             imgui,
             platform,
-            renderer,
             show_hide_key: Key::F3,
             is_showing: true,
             show_demo_window: false,
             show_logs: false,
+            show_log_levels: false,
             show_bricks: false,
+            show_diagnostics: safe_mode,
+            log_filter: LogFilter::new(),
+            frame_times: FrameTimes::new(),
         })
     }
 
@@ -130,6 +126,24 @@ impl Overlay {
         }]);
     }
 
+    /// Draws a whole frame on the render thread, between the renderer's `new_frame` and its `render` of imgui's draw data.
+    /// Only the renderer backends call it, so without one nothing is drawn.
+    #[cfg_attr(not(backend_dx9), allow(dead_code))]
+    pub fn frame(&mut self, subbrick_manager: &mut SubBrickManager, new_frame: impl FnOnce(), render: impl FnOnce(*mut imgui::sys::ImDrawData)) {
+        let started = Instant::now();
+
+        self.platform.new_frame();
+        new_frame();
+        self.draw(subbrick_manager);
+        let draw_data = unsafe {
+            imgui::sys::igRender();
+            imgui::sys::igGetDrawData()
+        };
+        render(draw_data);
+
+        self.frame_times.record(started);
+    }
+
     pub fn draw(&mut self, subbrick_manager: &mut SubBrickManager) {
         let ui = self.imgui.new_frame();
 
@@ -146,7 +160,9 @@ impl Overlay {
             ui.main_menu_bar(|| {
                 ui.menu("Blue Brick", || {
                     ui.menu_item_config("Show Logs").build_with_ref(&mut self.show_logs);
+                    ui.menu_item_config("Show Log Levels").build_with_ref(&mut self.show_log_levels);
                     ui.menu_item_config("Show Bricks").build_with_ref(&mut self.show_bricks);
+                    ui.menu_item_config("Show Diagnostics").build_with_ref(&mut self.show_diagnostics);
 
                     ui.separator();
 
@@ -161,33 +177,58 @@ impl Overlay {
                 });
 
                 ui.menu("Bricks", || {
-                    if ui.menu_item("Hello")  {
-
-                    }
+                    subbrick_manager.draw_bricks_menu(ui);
                 });
             });
 
             if self.show_logs {
-                Self::show_logs(ui, &mut self.show_logs);
+                Self::show_logs(ui, &mut self.show_logs, &mut self.log_filter);
+            }
+
+            if self.show_log_levels {
+                Self::show_log_levels(ui, &mut self.show_log_levels);
             }
 
             if self.show_bricks {
                 Self::show_bricks(ui, &mut self.show_bricks, subbrick_manager);
             }
 
+            if self.show_diagnostics {
+                Self::show_diagnostics(ui, &mut self.show_diagnostics, &self.frame_times);
+            }
+
             if self.show_demo_window {
                 ui.show_demo_window(&mut self.show_demo_window);
             }
 
             subbrick_manager.draw_all(ui);
         }
+
+        if Notifications::instance().draw(ui, &mut self.log_filter) {
+            self.is_showing = true;
+            self.show_logs = true;
+        }
     }
 
-    fn show_logs(ui: &Ui, opened: &mut bool) {
-        ui.window("Log Window").size([900.0, 650.0], Condition::FirstUseEver).horizontal_scrollbar(true).opened(opened).build(|| {
+    fn show_logs(ui: &Ui, opened: &mut bool, filter: &mut LogFilter) {
+        ui.window("Log Window").size([900.0, 650.0], Condition::FirstUseEver).opened(opened).build(|| {
             let mut main_logger = MainLogger::instance();
 
-            main_logger.draw_logs(ui);
+            main_logger.draw_logs(ui, filter);
+        });
+    }
+
+    fn show_log_levels(ui: &Ui, opened: &mut bool) {
+        ui.window("Log Levels").size([500.0, 300.0], Condition::FirstUseEver).opened(opened).build(|| {
+            LogLevels::instance().draw(ui);
+        });
+    }
+
+    fn show_diagnostics(ui: &Ui, opened: &mut bool, frame_times: &FrameTimes) {
+        ui.window("Diagnostics").size([600.0, 400.0], Condition::FirstUseEver).opened(opened).build(|| {
+            Diagnostics::instance().draw(ui);
+            ui.separator();
+            frame_times.draw(ui);
         });
     }
 
@@ -206,56 +247,34 @@ impl Overlay {
                     mods.end();
                 }
 
-                tab_bar.end();
-            }
-        });
-    }
+                if let Some(profiles) = ui.tab_item("Profiles") {
+                    subbrick_manager.draw_profile_list(ui);
 
-    pub fn post_draw(&self) {
-    }
+                    profiles.end();
+                }
 
-    pub fn handle_event(&mut self, event: OverlayEvent, subbrick_manager: &mut SubBrickManager) {
-        match event {
-            OverlayEvent::Draw(tx) => {
-                _ = tx.send(self.draw(subbrick_manager));
-            }
-            OverlayEvent::PostDraw(tx) => {
-                _ = tx.send(self.post_draw());
+                if let Some(packages) = ui.tab_item("Packages") {
+                    subbrick_manager.draw_package_list(ui);
+
+                    packages.end();
+                }
+
+                tab_bar.end();
             }
-            OverlayEvent::Platform(event) => self.platform.handle_event(event),
-            OverlayEvent::Renderer(event) => self.renderer.handle_event(event),
-        }
+        });
     }
 }
 
+/// The parts of the overlay the hooks can use without taking the lock
 pub struct OverlayHandle {
-    tx: Sender<BBEvent>,
+    #[cfg_attr(not(backend_win32), allow(dead_code))]
     platform: SomePlatformHandle,
-    #[allow(unused)]
-    renderer: SomeRendererHandle,
 }
 
 impl OverlayHandle {
-    pub fn new(config: Config, tx: Sender<BBEvent>) -> Self {
-        let platform = SomePlatformHandle::new(config, tx.clone());
-        let renderer = SomeRendererHandle::new(config, tx.clone());
-
+    pub fn new(config: Config) -> Self {
         Self {
-            tx,
-            platform,
-            renderer,
+            platform: SomePlatformHandle::new(config),
         }
     }
-
-    pub fn draw(&self) {
-        let (draw_tx, draw_rx) = mpsc::channel();
-        self.tx.send(OverlayEvent::Draw(draw_tx).into()).expect("Bluebrick thread died");
-        draw_rx.recv().expect("Bluebrick thread dropped draw_tx")
-    }
-
-    pub fn post_draw(&self) {
-        let (pdtx, pdrx) = mpsc::channel();
-        self.tx.send(OverlayEvent::PostDraw(pdtx).into()).expect("Bluebrick thread died");
-        pdrx.recv().expect("Bluebrick thread dropped pdrx")
-    }
 }
@@ -0,0 +1,181 @@
+use std::{ffi::{CStr, c_char}, sync::{Mutex, MutexGuard, OnceLock}, time::{Duration, Instant}};
+
+use bluebrick::{imgui::{Condition, MouseButton, StyleColor, Ui, WindowFlags}, logger::Severity, color::Color, web_colors::Rgb};
+
+use crate::logger::{MainLogger, filter::LogFilter};
+
+const TOAST_WIDTH: f32 = 360.0;
+const TOAST_MARGIN: f32 = 12.0;
+const MAX_TOASTS: usize = 6;
+
+pub(crate) struct Notification {
+    id: u64,
+    source: String,
+    title: String,
+    message: String,
+    severity: Severity,
+    sticky: bool,
+    created: Instant,
+    /// The log record written for this notification, revealed in the log window on click
+    log_id: u64,
+}
+
+impl Notification {
+    fn lifetime(&self) -> Duration {
+        match self.severity {
+            Severity::Warning | Severity::Error => Duration::from_secs(8),
+            _ => Duration::from_secs(5),
+        }
+    }
+
+    fn is_expired(&self) -> bool {
+        !self.sticky && self.created.elapsed() >= self.lifetime()
+    }
+
+    fn color(&self) -> [f32; 4] {
+        match self.severity {
+            Severity::Info => [0.25, 0.70, 1.0, 1.0],
+            Severity::Trace => [0.45, 0.45, 0.45, 1.0],
+            Severity::Debug => [0.6, 0.6, 0.6, 1.0],
+            Severity::Warning => [0.85, 0.65, 0.13, 1.0],
+            Severity::Error => [0.70, 0.13, 0.13, 1.0],
+        }
+    }
+
+    fn icon(&self) -> &'static str {
+        match self.severity {
+            Severity::Info => "ℹ️",
+            Severity::Trace => "🔍",
+            Severity::Debug => "🐞",
+            Severity::Warning => "⚠️",
+            Severity::Error => "⛔",
+        }
+    }
+}
+
+pub(crate) struct Notifications {
+    notifications: Vec<Notification>,
+    next_id: u64,
+}
+
+impl Notifications {
+    fn new() -> Self {
+        Self {
+            notifications: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn instance() -> MutexGuard<'static, Self> {
+        static NOTIFICATIONS: OnceLock<Mutex<Notifications>> = OnceLock::new();
+        NOTIFICATIONS.get_or_init(|| Mutex::new(Notifications::new())).lock().unwrap()
+    }
+
+    /// Logs the notification and queues a toast for it
+    pub fn notify(kind: &'static str, name: &str, name_color: Option<Color>, title: &str, message: &str, severity: Severity, sticky: bool) {
+        let log_id = MainLogger::instance().log_impl(kind, name, name_color, &format!("{title}: {message}"), severity);
+
+        let mut notifications = Self::instance();
+        let id = notifications.next_id;
+        notifications.next_id += 1;
+        notifications.notifications.push(Notification {
+            id,
+            source: String::from(name),
+            title: String::from(title),
+            message: String::from(message),
+            severity,
+            sticky,
+            created: Instant::now(),
+            log_id,
+        });
+    }
+
+    /// Draws the toasts stacked up from the bottom right corner, returns true if one was clicked to show the logs
+    pub fn draw(&mut self, ui: &Ui, log_filter: &mut LogFilter) -> bool {
+        self.notifications.retain(|notification| !notification.is_expired());
+
+        let display_size = ui.io().display_size;
+        let mut y = display_size[1] - TOAST_MARGIN;
+        let mut dismissed = Vec::new();
+        let mut show_logs = false;
+
+        for notification in self.notifications.iter().rev().take(MAX_TOASTS) {
+            let color = notification.color();
+            let _border = ui.push_style_color(StyleColor::Border, color);
+
+            let flags = WindowFlags::NO_DECORATION
+                | WindowFlags::ALWAYS_AUTO_RESIZE
+                | WindowFlags::NO_SAVED_SETTINGS
+                | WindowFlags::NO_FOCUS_ON_APPEARING
+                | WindowFlags::NO_NAV
+                | WindowFlags::NO_DOCKING;
+
+            let height = ui.window(format!("##Toast{}", notification.id))
+                .position([display_size[0] - TOAST_MARGIN, y], Condition::Always)
+                .position_pivot([1.0, 1.0])
+                .size_constraints([TOAST_WIDTH, 0.0], [TOAST_WIDTH, f32::MAX])
+                .bg_alpha(0.9)
+                .flags(flags)
+                .build(|| {
+                    ui.text_colored(color, format!("{} {}", notification.icon(), notification.title));
+                    ui.same_line();
+                    ui.text_disabled(format!("from {}", notification.source));
+
+                    if notification.sticky {
+                        ui.same_line_with_pos(TOAST_WIDTH - 30.0);
+                        if ui.small_button("x") {
+                            dismissed.push(notification.id);
+                        }
+                    }
+
+                    ui.text_wrapped(&notification.message);
+
+                    if ui.is_window_hovered() && ui.is_mouse_clicked(MouseButton::Left) && !ui.is_any_item_hovered() {
+                        log_filter.reveal(notification.log_id);
+                        show_logs = true;
+                        dismissed.push(notification.id);
+                    }
+
+                    ui.window_size()[1]
+                })
+                .unwrap_or_default();
+
+            y -= height + TOAST_MARGIN / 2.0;
+        }
+
+        self.notifications.retain(|notification| !dismissed.contains(&notification.id));
+
+        show_logs
+    }
+}
+
+fn notify_impl(kind: &'static str, name: *const c_char, name_color: Option<Color>, title: *const c_char, msg: *const c_char, severity: Severity, sticky: bool) {
+    let to_string = |string: *const c_char| {
+        let string = unsafe { CStr::from_ptr(string) };
+        String::from_utf8_lossy(string.to_bytes()).to_string()
+    };
+    Notifications::notify(kind, &to_string(name), name_color, &to_string(title), &to_string(msg), severity, sticky);
+}
+
+pub(crate) extern "C" fn notify_library_impl(name: *const c_char, name_color: Option<&Rgb>, title: *const c_char, msg: *const c_char, severity: Severity, sticky: bool) {
+    notify_impl("Library", name, name_color.copied().map(Color::from), title, msg, severity, sticky);
+}
+
+pub(crate) extern "C" fn notify_mod_impl(name: *const c_char, name_color: Option<&Rgb>, title: *const c_char, msg: *const c_char, severity: Severity, sticky: bool) {
+    notify_impl("Mod", name, name_color.copied().map(Color::from), title, msg, severity, sticky);
+}
+
+pub(crate) extern "C" fn notify_library_color_impl(name: *const c_char, name_color: Option<&Color>, title: *const c_char, msg: *const c_char, severity: Severity, sticky: bool) {
+    notify_impl("Library", name, name_color.copied(), title, msg, severity, sticky);
+}
+
+pub(crate) extern "C" fn notify_mod_color_impl(name: *const c_char, name_color: Option<&Color>, title: *const c_char, msg: *const c_char, severity: Severity, sticky: bool) {
+    notify_impl("Mod", name, name_color.copied(), title, msg, severity, sticky);
+}
+
+macro_rules! main_notify {
+    ($severity:ident, $sticky:expr, $title:expr, $($arg:tt)*) => {
+        crate::overlay::notifications::Notifications::notify("Loader", "BlueBrick", Some(bluebrick::web_colors::WebColor::DeepSkyBlue.into()), $title, &format!($($arg)*), bluebrick::logger::Severity::$severity, $sticky);
+    }
+}
+pub(crate) use main_notify;
@@ -1,54 +1,51 @@
+#[cfg(backend_win32)]
 pub mod win32;
 
 use std::error::Error;
-use std::sync::mpsc::Sender;
 
 use bluebrick_proxy::{Config, RequestedPlatform};
 
-use crate::BBEvent;
-use crate::overlay::OverlayEvent;
-use crate::overlay::platforms::win32::{Win32, Win32Event, Win32Handle};
-
-pub enum PlatformEvent {
-    Win32(Win32Event),
-}
-
-impl Into<BBEvent> for PlatformEvent {
-    fn into(self) -> BBEvent {
-        OverlayEvent::Platform(self).into()
-    }
-}
+use crate::logger::main_log;
+#[cfg(backend_win32)]
+use crate::overlay::platforms::win32::{Win32, Win32Handle};
 
 pub trait Platform {
     fn new_frame(&self);
 }
 
 pub enum SomePlatform {
+    #[cfg(backend_win32)]
     Win32(Win32),
 }
 
+/// Auto isn't detected yet, it defaults to Win32 since that is the only platform so far
+fn resolve(platform: RequestedPlatform) -> RequestedPlatform {
+    match platform {
+        RequestedPlatform::Auto => RequestedPlatform::Win32,
+        platform => platform,
+    }
+}
+
 impl SomePlatform {
     pub fn new(config: Config) -> Result<Self, Box<dyn Error>> {
-        Ok(match config.platform {
-            RequestedPlatform::Win32 => SomePlatform::Win32(Win32::new()?),
-        })
-    }
+        let platform = resolve(config.platform);
+        if config.platform == RequestedPlatform::Auto {
+            main_log!("Platform: auto defaults to {platform:?}, the only platform so far");
+        }
 
-    fn get_inner(&self) -> &dyn Platform {
-        match self {
-            Self::Win32(win32) => win32,
+        match platform {
+            #[cfg(backend_win32)]
+            RequestedPlatform::Win32 => Ok(SomePlatform::Win32(Win32::new()?)),
+            RequestedPlatform::Auto => unreachable!("auto is resolved above"),
+            #[allow(unreachable_patterns)]
+            platform => Err(format!("the {platform:?} platform is not built into this loader").into()),
         }
     }
 
-    pub fn handle_event(&mut self, event: PlatformEvent) {
-        match (event, self) {
-            (PlatformEvent::Win32(win32_event), SomePlatform::Win32(win32)) => {
-                win32.handle_event(win32_event);
-            }
-            // uncomment when there is more than 1 platform supported
-            /*_ => {
-                msgbox::create("Mismatched platform types", "A BlueBrick event was triggered with the wrong platform type", msgbox::IconType::Error);
-            }*/
+    fn get_inner(&self) -> &dyn Platform {
+        match *self {
+            #[cfg(backend_win32)]
+            Self::Win32(ref win32) => win32,
         }
     }
 }
@@ -59,30 +56,19 @@ impl Platform for SomePlatform {
     }
 }
 
-pub trait PlatformHandle {
-    fn new_frame(&self);
-}
-
 pub enum SomePlatformHandle {
+    #[cfg(backend_win32)]
     Win32(Win32Handle),
 }
 
 impl SomePlatformHandle {
-    pub fn new(config: Config, tx: Sender<BBEvent>) -> Self {
-        match config.platform {
-            RequestedPlatform::Win32 => SomePlatformHandle::Win32(Win32Handle::new(tx)),
-        }
-    }
-
-    fn get_inner(&self) -> &dyn PlatformHandle {
-        match self {
-            Self::Win32(win32) => win32,
+    pub fn new(config: Config) -> Self {
+        match resolve(config.platform) {
+            #[cfg(backend_win32)]
+            RequestedPlatform::Win32 => SomePlatformHandle::Win32(Win32Handle),
+            RequestedPlatform::Auto => unreachable!("auto is resolved above"),
+            #[allow(unreachable_patterns)]
+            platform => unreachable!("the overlay can't start with {platform:?}, it is not built in"),
         }
     }
 }
-
-impl PlatformHandle for SomePlatformHandle {
-    fn new_frame(&self) {
-        self.get_inner().new_frame();
-    }
-}
\ No newline at end of file
@@ -1,6 +1,6 @@
 #![allow(nonstandard_style)]
 
-use std::{error::Error, slice::from_raw_parts_mut, sync::mpsc::{self, Sender}};
+use std::{error::Error, slice::from_raw_parts_mut, sync::atomic::{AtomicUsize, Ordering}};
 
 use bluebrick::imgui;
 use dlopen::wrapper::{Container, WrapperApi};
@@ -17,9 +17,8 @@ use windows::{
     core::BOOL,
 };
 
-use crate::{BBEvent, BLUEBRICK_HANDLE};
-use crate::overlay::OverlayEvent;
-use crate::overlay::platforms::{Platform, PlatformEvent, PlatformHandle, SomePlatformHandle};
+use crate::BLUEBRICK_HANDLE;
+use crate::overlay::platforms::{Platform, SomePlatformHandle};
 
 unsafe extern "C" {
     fn _ImGui_ImplWin32_Init(hwnd: HWND) -> bool;
@@ -40,21 +39,11 @@ static_detour! {
     static SetCursorPosHook: unsafe extern "system" fn (i32, i32) -> BOOL;
 }
 
-pub enum Win32Event {
-    SetWindow { ptr: usize, tx: Sender<()> },
-    GetTrueWNDPROC { tx: Sender<WNDPROC> },
-    NewFrame { tx: Sender<()> },
-}
-
-impl Into<BBEvent> for Win32Event {
-    fn into(self) -> BBEvent {
-        BBEvent::Overlay(OverlayEvent::Platform(PlatformEvent::Win32(self)))
-    }
-}
+/// The game's own window procedure, read by every window message without a lock, 0 until the window is set
+static TRUE_WNDPROC: AtomicUsize = AtomicUsize::new(0);
 
 pub struct Win32 {
     window: HWND,
-    true_wndproc: WNDPROC,
 }
 
 impl Win32 {
@@ -88,9 +77,9 @@ impl Win32 {
                 _ => {}
             }
 
-            let true_wndproc = if let Some(bb) = BLUEBRICK_HANDLE.get() {
+            let true_wndproc = if let Some(overlay) = BLUEBRICK_HANDLE.get().and_then(|bb| bb.overlay.as_ref()) {
                 #[allow(irrefutable_let_patterns)]
-                if let SomePlatformHandle::Win32(win32) = &bb.overlay.platform {
+                if let SomePlatformHandle::Win32(win32) = &overlay.platform {
                     win32.get_true_wndproc()
                 } else {
                     return DefWindowProcA(hwnd, msg, wparam, lparam);
@@ -103,22 +92,27 @@ impl Win32 {
         result
     }
 
-    fn set_window(&mut self, ptr: *mut ()) {
+    /// Replaces the window's procedure with the overlay's, on the render thread the first time the game presents
+    pub fn set_window(&mut self, ptr: *mut ()) {
         unsafe {
-            if !self.window.is_invalid() && self.true_wndproc.is_some() {
-                SetWindowLongPtrA(self.window, GWLP_WNDPROC, self.true_wndproc.unwrap() as *const () as _);
+            if let Some(true_wndproc) = Self::get_true_wndproc()
+                && !self.window.is_invalid()
+            {
+                SetWindowLongPtrA(self.window, GWLP_WNDPROC, true_wndproc as *const () as _);
             }
 
             self.window = HWND(ptr.cast());
 
-            self.true_wndproc = std::mem::transmute(SetWindowLongPtrA(self.window, GWLP_WNDPROC, Self::FakeWndProc as *const () as _));
+            let true_wndproc = SetWindowLongPtrA(self.window, GWLP_WNDPROC, Self::FakeWndProc as *const () as _);
+            TRUE_WNDPROC.store(true_wndproc as usize, Ordering::Release);
 
             _ImGui_ImplWin32_Init(self.window);
         }
     }
 
-    fn get_true_wndproc(&self) -> WNDPROC {
-        self.true_wndproc
+    fn get_true_wndproc() -> WNDPROC {
+        // WNDPROC is an optional function pointer, so 0 is None
+        unsafe { std::mem::transmute::<usize, WNDPROC>(TRUE_WNDPROC.load(Ordering::Acquire)) }
     }
 
     fn attach_hooks() -> Result<(), Box<dyn Error>> {
@@ -133,12 +127,15 @@ impl Win32 {
                 RegisterRawInputDevicesHook.call(raw_input_devices, num_devices, size)
             })?;
             RegisterRawInputDevicesHook.enable()?;
+            crate::crash::add_hook("RegisterRawInputDevices", cont.RegisterRawInputDevices as usize);
 
             ShowCursorHook.initialize(cont.ShowCursor, |_| ShowCursorHook.call(TRUE))?;
             ShowCursorHook.enable()?;
+            crate::crash::add_hook("ShowCursor", cont.ShowCursor as usize);
 
             SetCursorPosHook.initialize(cont.SetCursorPos, |_, _| FALSE)?;
             SetCursorPosHook.enable()?;
+            crate::crash::add_hook("SetCursorPos", cont.SetCursorPos as usize);
         }
 
         Ok(())
@@ -154,23 +151,8 @@ impl Win32 {
         Self::init()?;
         Ok(Self {
             window: Default::default(),
-            true_wndproc: None,
         })
     }
-
-    pub fn handle_event(&mut self, event: Win32Event) {
-        match event {
-            Win32Event::SetWindow { ptr, tx } => {
-                _ = tx.send(self.set_window(ptr as _));
-            }
-            Win32Event::GetTrueWNDPROC { tx } => {
-                _ = tx.send(self.get_true_wndproc());
-            }
-            Win32Event::NewFrame { tx } => {
-                _ = tx.send(self.new_frame());
-            }
-        }
-    }
 }
 
 impl Platform for Win32 {
@@ -181,34 +163,10 @@ impl Platform for Win32 {
     }
 }
 
-pub struct Win32Handle {
-    tx: Sender<BBEvent>,
-}
+pub struct Win32Handle;
 
 impl Win32Handle {
-    pub fn new(tx: Sender<BBEvent>) -> Self {
-        Self  {
-            tx
-        }
-    }
-
-    pub fn set_window(&self, ptr: *mut ()) {
-        let (swtx, swrx) = mpsc::channel();
-        _ = self.tx.send(Win32Event::SetWindow { ptr: ptr as _, tx: swtx }.into());
-        swrx.recv().expect("Bluebrick thread dropped swtx")
-    }
-
     pub fn get_true_wndproc(&self) -> WNDPROC {
-        let (gtwtx, gtwrx) = mpsc::channel();
-        _ = self.tx.send(Win32Event::GetTrueWNDPROC { tx: gtwtx }.into());
-        gtwrx.recv().expect("Bluebrick thread dropped gtwtx")
-    }
-}
-
-impl PlatformHandle for Win32Handle {
-    fn new_frame(&self) {
-        let (nftx, nfrx) = mpsc::channel();
-        _ = self.tx.send(Win32Event::NewFrame { tx: nftx }.into());
-        nfrx.recv().expect("Bluebrick thread dropped nfrx")
+        Win32::get_true_wndproc()
     }
 }
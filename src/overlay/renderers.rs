@@ -1,86 +1,48 @@
+pub mod detect;
+#[cfg(backend_dx9)]
 pub mod dx9;
 
 use std::error::Error;
-use std::sync::mpsc::Sender;
 
 use bluebrick_proxy::{Config, RequestedRenderer};
 
-use crate::BBEvent;
-use crate::overlay::OverlayEvent;
-use crate::overlay::renderers::dx9::{DX9, DX9Event, DX9Handle};
+use crate::logger::{main_log, main_log_warning};
+#[cfg(backend_dx9)]
+use crate::overlay::renderers::dx9::DX9;
 
-pub enum RendererEvent {
-    DX9(DX9Event),
-}
-
-impl Into<BBEvent> for RendererEvent {
-    fn into(self) -> BBEvent {
-        OverlayEvent::Renderer(self).into()
-    }
-}
-
-pub trait Renderer {
-
-}
-
-pub enum SomeRenderer {
-    DX9(DX9),
-}
-
-impl SomeRenderer {
-    pub fn new(config: Config) -> Result<Self, Box<dyn Error>> {
-        Ok(match config.renderer {
-            RequestedRenderer::DX9 => SomeRenderer::DX9(DX9::new()?),
-        })
+/// Hooks the renderer the config asks for, or every supported one for `Auto` until the game presents with one of them
+pub fn arm(config: Config) -> Result<(), Box<dyn Error>> {
+    if config.renderer != RequestedRenderer::Auto {
+        detect::bind(config.renderer);
+        return arm_requested(config.renderer);
     }
 
-    #[allow(unused)]
-    fn get_inner(&self) -> &dyn Renderer {
-        match self {
-            Self::DX9(dx9) => dx9,
-        }
-    }
-
-    pub fn handle_event(&mut self, event: RendererEvent) {
-        match (event, self) {
-            (RendererEvent::DX9(dx9_event), SomeRenderer::DX9(dx9)) => {
-                dx9.handle_event(dx9_event);
+    main_log!("Renderer detection: arming {:?}", detect::SUPPORTED);
+    let mut armed = 0;
+    let mut errors = Vec::new();
+    for &renderer in detect::SUPPORTED {
+        match arm_requested(renderer) {
+            Ok(()) => armed += 1,
+            Err(e) => {
+                main_log_warning!("Renderer detection: could not arm {renderer:?}: {e}");
+                errors.push(format!("{renderer:?}: {e}"));
             }
-            // uncomment when there is more than 1 renderer supported
-            /*_ => {
-                msgbox::create("Mismatched renderer types", "A BlueBrick event was triggered with the wrong renderer type", msgbox::IconType::Error);
-            }*/
         }
     }
-}
 
-impl Renderer for SomeRenderer {
-
-}
-
-pub trait RendererHandle {
-
-}
-
-pub enum SomeRendererHandle {
-    DX9(DX9Handle),
-}
-
-impl SomeRendererHandle {
-    pub fn new(config: Config, tx: Sender<BBEvent>) -> Self {
-        match config.renderer {
-            RequestedRenderer::DX9 => SomeRendererHandle::DX9(DX9Handle::new(tx)),
-        }
+    if armed == 0 {
+        return Err(format!("no renderer could be armed ({})", errors.join(", ")).into());
     }
+    Ok(())
+}
 
-    #[allow(unused)]
-    fn get_inner(&self) -> &dyn RendererHandle {
-        match self {
-            Self::DX9(dx9) => dx9,
-        }
+fn arm_requested(renderer: RequestedRenderer) -> Result<(), Box<dyn Error>> {
+    match renderer {
+        #[cfg(backend_dx9)]
+        RequestedRenderer::DX9 => DX9::arm(),
+        RequestedRenderer::Auto => Err("auto is not a renderer".into()),
+        #[allow(unreachable_patterns)]
+        renderer => Err(format!("the {renderer:?} renderer is not built into this loader").into()),
     }
 }
 
-impl RendererHandle for SomeRendererHandle {
-
-}
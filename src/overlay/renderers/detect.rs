@@ -0,0 +1,59 @@
+use std::{error::Error, sync::{Mutex, OnceLock}};
+
+use bluebrick_proxy::RequestedRenderer;
+
+use crate::logger::{main_log, main_log_warning};
+#[cfg(backend_dx9)]
+use crate::overlay::renderers::dx9::DX9;
+
+/// Every renderer built into this loader, which are all armed when the config asks for `Auto`
+pub const SUPPORTED: &[RequestedRenderer] = &[
+    #[cfg(backend_dx9)]
+    RequestedRenderer::DX9,
+];
+
+/// The renderer the overlay draws with, set from the config or by the first one the game presents with
+static BOUND: OnceLock<RequestedRenderer> = OnceLock::new();
+
+/// Binds the overlay to a renderer from the config, so detection never happens
+pub fn bind(renderer: RequestedRenderer) {
+    let _ = BOUND.set(renderer);
+}
+
+/// Logs the first device each renderer creates, which is useful when the game tries several before settling
+#[cfg_attr(not(backend_dx9), allow(dead_code))]
+pub fn device_created(renderer: RequestedRenderer) {
+    static SEEN: Mutex<Vec<RequestedRenderer>> = Mutex::new(Vec::new());
+
+    let mut seen = SEEN.lock().unwrap();
+    if !seen.contains(&renderer) {
+        seen.push(renderer);
+        main_log!("Renderer detection: the game created a {renderer:?} device");
+    }
+}
+
+/// Called by each renderer's present hook, returns true if the overlay should draw with that renderer.
+/// The first renderer to present is bound and the others' hooks are released.
+#[cfg_attr(not(backend_dx9), allow(dead_code))]
+pub fn presenting(renderer: RequestedRenderer) -> bool {
+    *BOUND.get_or_init(|| {
+        main_log!("Renderer detection: the game presents with {renderer:?}, binding the overlay to it");
+        for &other in SUPPORTED.iter().filter(|&&other| other != renderer) {
+            release(other);
+        }
+        renderer
+    }) == renderer
+}
+
+fn release(renderer: RequestedRenderer) {
+    let result: Result<(), Box<dyn Error>> = match renderer {
+        #[cfg(backend_dx9)]
+        RequestedRenderer::DX9 => DX9::release(),
+        renderer => Err(format!("{renderer:?} is not built into this loader").into()),
+    };
+
+    match result {
+        Ok(()) => main_log!("Renderer detection: released the unused {renderer:?} hooks"),
+        Err(e) => main_log_warning!("Renderer detection: could not release the unused {renderer:?} hooks: {e}"),
+    }
+}
@@ -1,8 +1,9 @@
 #![allow(nonstandard_style)]
 
-use std::{error::Error, sync::{OnceLock, mpsc::Sender}};
+use std::{error::Error, sync::OnceLock};
 
 use bluebrick::imgui;
+use bluebrick_proxy::RequestedRenderer;
 use dlopen::wrapper::{Container, WrapperApi};
 use dlopen_derive::WrapperApi;
 use retour::static_detour;
@@ -14,9 +15,10 @@ use windows::{
     core::HRESULT,
 };
 
-use crate::{BBEvent, BLUEBRICK_HANDLE};
-use crate::overlay::renderers::{Renderer, RendererHandle};
-use crate::overlay::platforms::{PlatformHandle, SomePlatformHandle};
+use crate::{BLUEBRICK_HANDLE, show_error};
+use crate::logger::main_log_error;
+use crate::overlay::renderers::detect;
+use crate::overlay::platforms::SomePlatform;
 
 unsafe extern "C" {
     fn _ImGui_ImplDX9_Init(device: *mut *const IDirect3DDevice9_Vtbl) -> bool;
@@ -41,14 +43,18 @@ static_detour! {
     static Direct3D9_Device_Present: unsafe extern "system" fn(*mut *const IDirect3DDevice9_Vtbl, *const RECT, *const RECT, HWND, *const RGNDATA) -> HRESULT;
 }
 
-pub enum DX9Event {
-
+/// Hooks fail while the game is making its device, before the overlay can show anything, so this shows a message box
+fn hook_failed(title: &str, e: impl std::fmt::Display) {
+    main_log_error!("{title}: {e}");
+    show_error(title, &format!("Error: {e}"));
 }
 
 pub struct DX9 {}
 
 impl DX9 {
     fn hook_device(device: *const *const IDirect3DDevice9_Vtbl) {
+        detect::device_created(RequestedRenderer::DX9);
+
         static CALL_ONLY_ONCE: OnceLock<()> = OnceLock::new();
 
         CALL_ONLY_ONCE.get_or_init(|| {
@@ -65,11 +71,14 @@ impl DX9 {
                     return result;
                 }) {
                     Err(e) => {
-                        let _ = msgbox::create("Could not hook dx9 Reset", &format!("Error: {e:}"), msgbox::IconType::Error);
+                        hook_failed("Could not hook dx9 Reset", e);
                     }
                     Ok(_) => {
-                        if let Err(e) = Direct3D9_Device_Reset.enable() {
-                            let _ = msgbox::create("Could not enable dx9 Reset hook", &format!("Error: {e:}"), msgbox::IconType::Error);
+                        match Direct3D9_Device_Reset.enable() {
+                            Err(e) => {
+                                hook_failed("Could not enable dx9 Reset hook", e);
+                            }
+                            Ok(_) => crate::crash::add_hook("dx9 Reset", (**device).Reset as usize),
                         }
                     }
                 }
@@ -79,45 +88,39 @@ impl DX9 {
                 let real_present = std::mem::transmute(real_present);
 
                 match Direct3D9_Device_Present.initialize(real_present, |this, source_rect, dest_rect, dest_window_override, dirty_region| {
-                    if let Some(bb) = BLUEBRICK_HANDLE.get() {
-                        static CALL_ONLY_ONCE: OnceLock<()> = OnceLock::new();
-                        CALL_ONLY_ONCE.get_or_init(|| {
-                            let mut params = Default::default();
-                            let _ = ((**this).GetCreationParameters)(this.cast(), &mut params);
-                            #[allow(irrefutable_let_patterns)]
-                            if let SomePlatformHandle::Win32(win32) = &bb.overlay.platform {
-                                win32.set_window(params.hFocusWindow.0.cast());
-                            }
-
-                            _ImGui_ImplDX9_Init(this);
+                    let bb = BLUEBRICK_HANDLE.get().filter(|bb| bb.overlay.is_some());
+                    if let Some(bb) = bb.filter(|_| detect::presenting(RequestedRenderer::DX9)) {
+                        // drawn right here on the render thread, the game only waits on the lock
+                        bb.with_overlay(|overlay, subbrick_manager| {
+                            static CALL_ONLY_ONCE: OnceLock<()> = OnceLock::new();
+                            CALL_ONLY_ONCE.get_or_init(|| {
+                                let mut params = Default::default();
+                                let _ = ((**this).GetCreationParameters)(this.cast(), &mut params);
+                                #[allow(irrefutable_let_patterns)]
+                                if let SomePlatform::Win32(win32) = &mut overlay.platform {
+                                    win32.set_window(params.hFocusWindow.0.cast());
+                                }
+
+                                _ImGui_ImplDX9_Init(this);
+                            });
+
+                            overlay.frame(subbrick_manager, || _ImGui_ImplDX9_NewFrame(), |draw_data| _ImGui_ImplDX9_RenderDrawData(draw_data));
                         });
 
-                        bb.overlay.platform.new_frame();
-                        _ImGui_ImplDX9_NewFrame();
-
-                        bb.overlay.draw();
-                        let draw_data = {
-                            imgui::sys::igRender();
-                            imgui::sys::igGetDrawData()
-                        };
-
-                        _ImGui_ImplDX9_RenderDrawData(draw_data);
-
-                        let result = Direct3D9_Device_Present.call(this, source_rect, dest_rect, dest_window_override, dirty_region);
-
-                        bb.overlay.post_draw();
-
-                        return result;
+                        Direct3D9_Device_Present.call(this, source_rect, dest_rect, dest_window_override, dirty_region)
                     } else {
                         Direct3D9_Device_Present.call(this, source_rect, dest_rect, dest_window_override, dirty_region)
                     }
                 }) {
                     Err(e) => {
-                        let _ = msgbox::create("Could not hook dx9 Present", &format!("Error: {e:}"), msgbox::IconType::Error);
+                        hook_failed("Could not hook dx9 Present", e);
                     }
                     Ok(_) => {
-                        if let Err(e) = Direct3D9_Device_Present.enable() {
-                            let _ = msgbox::create("Could not enable dx9 Present hook", &format!("Error: {e:}"), msgbox::IconType::Error);
+                        match Direct3D9_Device_Present.enable() {
+                            Err(e) => {
+                                hook_failed("Could not enable dx9 Present hook", e);
+                            }
+                            Ok(_) => crate::crash::add_hook("dx9 Present", (**device).Present as usize),
                         }
                     }
                 }
@@ -139,11 +142,14 @@ impl DX9 {
                 result
             }) {
                 Err(e) => {
-                    let _ = msgbox::create("Could not hook dx9 CreateDevice", &format!("Error: {e:}"), msgbox::IconType::Error);
+                    hook_failed("Could not hook dx9 CreateDevice", e);
                 }
                 Ok(_) => {
-                    if let Err(e) = Direct3D9_CreateDeviceHook.enable() {
-                        let _ = msgbox::create("Could not enable dx9 CreateDevice hook", &format!("Error: {e:}"), msgbox::IconType::Error);
+                    match Direct3D9_CreateDeviceHook.enable() {
+                        Err(e) => {
+                            hook_failed("Could not enable dx9 CreateDevice hook", e);
+                        }
+                        Ok(_) => crate::crash::add_hook("dx9 CreateDevice", (**id3d9).CreateDevice as usize),
                     }
                 }
             }
@@ -167,11 +173,14 @@ impl DX9 {
                 },
             ) {
                 Err(e) => {
-                    let _ = msgbox::create("Could not hook dx9 CreateDeviceEx", &format!("Error: {e:}"), msgbox::IconType::Error);
+                    hook_failed("Could not hook dx9 CreateDeviceEx", e);
                 }
                 Ok(_) => {
-                    if let Err(e) = Direct3D9_CreateDeviceExHook.enable() {
-                        let _ = msgbox::create("Could not enable dx9 CreateDeviceEx hook", &format!("Error: {e:}"), msgbox::IconType::Error);
+                    match Direct3D9_CreateDeviceExHook.enable() {
+                        Err(e) => {
+                            hook_failed("Could not enable dx9 CreateDeviceEx hook", e);
+                        }
+                        Ok(_) => crate::crash::add_hook("dx9 CreateDeviceEx", (**id3d9ex).CreateDeviceEx as usize),
                     }
                 }
             };
@@ -188,6 +197,7 @@ impl DX9 {
                 id3d9
             })?;
             Direct3DCreate9Hook.enable()?;
+            crate::crash::add_hook("Direct3DCreate9", cont.Direct3DCreate9 as usize);
 
             Direct3DCreate9ExHook.initialize(cont.Direct3DCreate9Ex, |sdkversion, id3d9ex| {
                 let result = Direct3DCreate9ExHook.call(sdkversion, id3d9ex);
@@ -196,50 +206,28 @@ impl DX9 {
                 result
             })?;
             Direct3DCreate9ExHook.enable()?;
+            crate::crash::add_hook("Direct3DCreate9Ex", cont.Direct3DCreate9Ex as usize);
         }
 
         Ok(())
     }
 
-    fn init() -> Result<(), Box<dyn Error>> {
-        Self::attach_hooks()?;
-
-        Ok(())
-    }
-
-    pub fn new() -> Result<Self, Box<dyn Error>> {
-        Self::init()?;
-        Ok(Self {})
+    pub fn arm() -> Result<(), Box<dyn Error>> {
+        Self::attach_hooks()
     }
 
-    pub fn handle_event(&mut self, event: DX9Event) {
-        match event {
-
+    /// Disables every dx9 hook, for when detection picked another renderer
+    pub fn release() -> Result<(), Box<dyn Error>> {
+        macro_rules! disable {
+            ($($hook:ident),*) => {
+                $(if $hook.is_enabled() {
+                    unsafe { $hook.disable()?; }
+                })*
+            }
         }
-    }
-}
 
-impl Renderer for DX9 {
-
-}
-
-pub struct DX9Handle {
-    #[allow(unused)]
-    tx: Sender<BBEvent>,
-}
-
-impl DX9Handle {
-    pub fn new(tx: Sender<BBEvent>) -> Self {
-        Self {
-            tx
-        }
+        disable!(Direct3D9_Device_Present, Direct3D9_Device_Reset, Direct3D9_CreateDeviceHook, Direct3D9_CreateDeviceExHook, Direct3DCreate9Hook, Direct3DCreate9ExHook);
+        Ok(())
     }
 }
 
-impl DX9Handle {
-
-}
-
-impl RendererHandle for DX9Handle {
-
-}
@@ -0,0 +1,502 @@
+//! `.brick` packages, which bundle a brick's binaries for each platform with its assets, readme and licence.
+//!
+//! A package is a zip archive with a `brick.toml` manifest at its root:
+//!
+//! ```toml
+//! id = "cool-mod"
+//! name = "Cool Mod"
+//! version = "1.2.0"
+//! kind = "mod"
+//! author = "Someone"
+//! description = "Makes the game cooler"
+//! readme = "README.md"
+//! license = "LICENSE"
+//!
+//! [binaries]
+//! windows-x86 = "bin/windows-x86/cool_mod.dll"
+//! linux-x86_64 = "bin/linux-x86_64/libcool_mod.so"
+//! ```
+//!
+//! Archives dropped in `install` under the brick dir are installed when the game starts, into
+//! `packages/<id>/<version>`, and replace any other version of the same package.
+
+use std::{collections::BTreeMap, env, fmt, fs::{self, File}, io::{self, Read}, path::{Component, Path, PathBuf}};
+
+use bluebrick::imgui::{TreeNodeFlags, Ui};
+use semver::Version;
+use serde::Deserialize;
+use zip::ZipArchive;
+
+use crate::logger::{main_log, main_log_debug, main_log_error, main_log_warning};
+use crate::overlay::notifications::main_notify;
+
+pub(crate) const EXTENSION: &str = "brick";
+const MANIFEST_NAME: &str = "brick.toml";
+const PACKAGES_FOLDER: &str = "packages";
+const INSTALL_FOLDER: &str = "install";
+/// Holds the installed version of a package, written last so a half extracted package is never used
+const ACTIVE_NAME: &str = "active";
+/// Marks a package to be removed when the game next starts, since its binary is loaded until then
+const REMOVE_NAME: &str = "remove";
+
+/// The binary key for this build of the loader, like `windows-x86` or `linux-x86_64`
+pub(crate) fn platform() -> String {
+    format!("{}-{}", env::consts::OS, env::consts::ARCH)
+}
+
+#[derive(Clone, Copy, PartialEq, Default, Deserialize, Debug)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum PackageKind {
+    Library,
+    #[default]
+    Mod,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct PackageManifest {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub kind: PackageKind,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub description: String,
+    pub readme: Option<String>,
+    pub license: Option<String>,
+    /// Platform to the binary's path in the archive
+    #[serde(default)]
+    pub binaries: BTreeMap<String, String>,
+}
+
+impl PackageManifest {
+    fn parse(text: &str) -> Result<Self, String> {
+        let manifest: Self = toml::from_str(text).map_err(|e| format!("{MANIFEST_NAME} is invalid: {}", e.message()))?;
+
+        // the id becomes a folder name
+        let valid_id = !manifest.id.is_empty() && !manifest.id.starts_with('.')
+            && manifest.id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        if !valid_id {
+            return Err(format!("{:?} is not a valid package id, use letters, numbers, -, _ and .", manifest.id));
+        }
+        manifest.semver()?;
+
+        // the paths are joined onto the package's folder, so they must stay inside it
+        let binaries = manifest.binaries.iter().map(|(platform, binary)| (format!("the {platform} binary"), binary));
+        let texts = [("the readme", &manifest.readme), ("the license", &manifest.license)]
+            .into_iter()
+            .filter_map(|(what, path)| path.as_ref().map(|path| (String::from(what), path)));
+        for (what, path) in binaries.chain(texts) {
+            if !is_enclosed(path) {
+                return Err(format!("{what} {path:?} is not a relative path inside the package"));
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    fn semver(&self) -> Result<Version, String> {
+        Version::parse(&self.version).map_err(|e| format!("{:?} is not a valid version: {e}", self.version))
+    }
+
+    /// The readme is `README.md` unless the manifest names another file
+    fn readme(&self) -> &str {
+        self.readme.as_deref().unwrap_or("README.md")
+    }
+}
+
+/// Whether a path from a manifest or archive stays inside the folder it is joined onto, on every platform.
+/// Backslashes and colons are refused because Windows reads them as separators and drive prefixes.
+fn is_enclosed(path: &str) -> bool {
+    !path.is_empty()
+        && !path.contains(['\\', ':'])
+        && Path::new(path).components().all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+/// What installing an archive did to the package it holds
+enum InstallOutcome {
+    Installed,
+    Upgraded(String),
+    Reinstalled,
+    Downgraded(String),
+}
+
+impl fmt::Display for InstallOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InstallOutcome::Installed => write!(f, "newly installed"),
+            InstallOutcome::Upgraded(from) => write!(f, "upgraded from v{from}"),
+            InstallOutcome::Reinstalled => write!(f, "reinstalled"),
+            InstallOutcome::Downgraded(from) => write!(f, "downgraded from v{from}"),
+        }
+    }
+}
+
+pub(crate) struct Package {
+    pub manifest: PackageManifest,
+    /// The versioned folder the package is installed in
+    pub dir: PathBuf,
+    /// Every installed file relative to `dir`, with its size
+    files: Vec<(PathBuf, u64)>,
+    readme: Option<String>,
+    license: Option<String>,
+    remove: bool,
+}
+
+impl Package {
+    /// The binary for this platform, if the package has one
+    pub fn binary(&self) -> Option<PathBuf> {
+        self.manifest.binaries.get(&platform()).map(|binary| self.dir.join(binary))
+    }
+
+    fn read(package_dir: &Path) -> Result<Self, String> {
+        let version = fs::read_to_string(package_dir.join(ACTIVE_NAME)).map_err(|e| format!("no installed version: {e}"))?;
+        if !is_enclosed(version.trim()) {
+            return Err(format!("the installed version {:?} is not a folder inside the package", version.trim()));
+        }
+        let dir = package_dir.join(version.trim());
+        let manifest = fs::read_to_string(dir.join(MANIFEST_NAME)).map_err(|e| format!("could not read {MANIFEST_NAME}: {e}"))?;
+        let manifest = PackageManifest::parse(&manifest)?;
+
+        let mut files = Vec::new();
+        list_files(&dir, Path::new(""), &mut files).map_err(|e| format!("could not list the installed files: {e}"))?;
+        let text = |name: Option<&str>| name.and_then(|name| fs::read_to_string(dir.join(name)).ok());
+
+        Ok(Self {
+            readme: text(Some(manifest.readme())),
+            license: text(manifest.license.as_deref()),
+            manifest,
+            files,
+            remove: package_dir.join(REMOVE_NAME).exists(),
+            dir,
+        })
+    }
+
+    fn draw(&mut self, ui: &Ui, packages_dir: &Path) {
+        let manifest = &self.manifest;
+        let label = format!("{} v{}###{}", manifest.name, manifest.version, manifest.id);
+        if !ui.collapsing_header(label, TreeNodeFlags::empty()) {
+            return;
+        }
+
+        ui.indent();
+        let kind = match manifest.kind {
+            PackageKind::Library => "Library",
+            PackageKind::Mod => "Mod",
+        };
+        ui.text(format!("{kind} {} by {}", manifest.id, manifest.author));
+        if !manifest.description.is_empty() {
+            ui.text_wrapped(&manifest.description);
+        }
+        ui.text_disabled(format!("Installed in {}", self.dir.display()));
+        match self.binary() {
+            Some(binary) => ui.text_disabled(format!("Loads {}", binary.strip_prefix(&self.dir).unwrap_or(&binary).display())),
+            None => ui.text_colored([1.0, 0.6, 0.2, 1.0], format!("Has no binary for {}", platform())),
+        }
+
+        if let Some(_files) = ui.tree_node(format!("Files ({})", self.files.len())) {
+            for (file, size) in &self.files {
+                ui.text(file.display().to_string());
+                ui.same_line();
+                ui.text_disabled(format_size(*size));
+            }
+        }
+        if let Some(readme) = &self.readme
+            && let Some(_readme) = ui.tree_node("Readme")
+        {
+            ui.text_wrapped(readme);
+        }
+        if let Some(license) = &self.license
+            && let Some(_license) = ui.tree_node("License")
+        {
+            ui.text_wrapped(license);
+        }
+
+        let marker = packages_dir.join(&self.manifest.id).join(REMOVE_NAME);
+        if self.remove {
+            ui.text_colored([1.0, 0.6, 0.2, 1.0], "Will be removed when the game restarts");
+            ui.same_line();
+            if ui.button("Keep") {
+                match fs::remove_file(&marker) {
+                    Ok(()) => self.remove = false,
+                    Err(e) => main_log_error!("Could not keep {}: {e}", self.manifest.id),
+                }
+            }
+        } else if ui.button("Uninstall") {
+            match fs::write(&marker, "") {
+                Ok(()) => self.remove = true,
+                Err(e) => main_log_error!("Could not mark {} for removal: {e}", self.manifest.id),
+            }
+        }
+        ui.unindent();
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{bytes} B"),
+        1024..1048576 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / 1048576.0),
+    }
+}
+
+fn list_files(root: &Path, relative: &Path, files: &mut Vec<(PathBuf, u64)>) -> io::Result<()> {
+    let mut entries = fs::read_dir(root.join(relative))?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let path = relative.join(entry.file_name());
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            list_files(root, &path, files)?;
+        } else {
+            files.push((path, metadata.len()));
+        }
+    }
+    Ok(())
+}
+
+/// Installs the package in an archive, replacing any other installed version of it
+fn install(packages_dir: &Path, archive_path: &Path) -> Result<(PackageManifest, InstallOutcome), String> {
+    let file = File::open(archive_path).map_err(|e| format!("could not open it: {e}"))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("it is not a .{EXTENSION} archive: {e}"))?;
+
+    let mut text = String::new();
+    archive
+        .by_name(MANIFEST_NAME)
+        .map_err(|e| format!("it has no {MANIFEST_NAME}: {e}"))?
+        .read_to_string(&mut text)
+        .map_err(|e| format!("could not read {MANIFEST_NAME}: {e}"))?;
+    let manifest = PackageManifest::parse(&text)?;
+
+    for (platform, binary) in &manifest.binaries {
+        if archive.index_for_name(binary).is_none() {
+            return Err(format!("the {platform} binary {binary} is missing"));
+        }
+    }
+    if !manifest.binaries.contains_key(&platform()) {
+        return Err(format!("it has no binary for {}, only {}", platform(), manifest.binaries.keys().cloned().collect::<Vec<_>>().join(", ")));
+    }
+
+    let package_dir = packages_dir.join(&manifest.id);
+    let previous = fs::read_to_string(package_dir.join(ACTIVE_NAME)).ok().map(|version| version.trim().to_string());
+
+    // extracted next to the final folder, so a failed install leaves the old version working
+    let partial = package_dir.join(format!("{}.partial", manifest.version));
+    let _ = fs::remove_dir_all(&partial);
+    fs::create_dir_all(&partial).map_err(|e| format!("could not make {}: {e}", partial.display()))?;
+    if let Err(e) = extract(&mut archive, &partial) {
+        let _ = fs::remove_dir_all(&partial);
+        // only goes if no other version was left in it
+        let _ = fs::remove_dir(&package_dir);
+        return Err(format!("could not extract it: {e}"));
+    }
+
+    let version_dir = package_dir.join(&manifest.version);
+    let _ = fs::remove_dir_all(&version_dir);
+    fs::rename(&partial, &version_dir).map_err(|e| format!("could not move it into {}: {e}", version_dir.display()))?;
+    fs::write(package_dir.join(ACTIVE_NAME), &manifest.version).map_err(|e| format!("could not mark v{} as installed: {e}", manifest.version))?;
+    let _ = fs::remove_file(package_dir.join(REMOVE_NAME));
+
+    // nothing is loaded yet, so the old versions can go right away
+    for entry in fs::read_dir(&package_dir).into_iter().flatten().flatten() {
+        if entry.file_name() != manifest.version.as_str()
+            && entry.path().is_dir()
+            && let Err(e) = fs::remove_dir_all(entry.path())
+        {
+            main_log_warning!("Could not remove {} from {}: {e}", entry.file_name().to_string_lossy(), manifest.id);
+        }
+    }
+
+    let outcome = match previous.as_deref().map(Version::parse) {
+        None => InstallOutcome::Installed,
+        Some(Ok(previous)) if previous == manifest.semver()? => InstallOutcome::Reinstalled,
+        Some(Ok(previous)) if previous < manifest.semver()? => InstallOutcome::Upgraded(previous.to_string()),
+        Some(_) => InstallOutcome::Downgraded(previous.unwrap_or_default()),
+    };
+    Ok((manifest, outcome))
+}
+
+fn extract(archive: &mut ZipArchive<File>, to: &Path) -> Result<(), String> {
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
+        // a package with paths that would escape its folder isn't installed at all
+        let path = match file.enclosed_name() {
+            Some(path) if is_enclosed(file.name()) => path,
+            _ => return Err(format!("{} points outside of the package", file.name())),
+        };
+
+        let out = to.join(path);
+        if file.is_dir() {
+            fs::create_dir_all(&out).map_err(|e| format!("{}: {e}", out.display()))?;
+            continue;
+        }
+        if let Some(parent) = out.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("{}: {e}", parent.display()))?;
+        }
+        let mut out_file = File::create(&out).map_err(|e| format!("{}: {e}", out.display()))?;
+        io::copy(&mut file, &mut out_file).map_err(|e| format!("{}: {e}", out.display()))?;
+    }
+    Ok(())
+}
+
+pub(crate) struct Packages {
+    packages_dir: PathBuf,
+    install_dir: PathBuf,
+    installed: Vec<Package>,
+}
+
+impl Packages {
+    /// Removes the packages marked for removal and installs the archives waiting in `install`, before any brick is loaded
+    pub fn new(brick_dir: &Path) -> Self {
+        let mut new = Self {
+            packages_dir: brick_dir.join(PACKAGES_FOLDER),
+            install_dir: brick_dir.join(INSTALL_FOLDER),
+            installed: Vec::new(),
+        };
+
+        for folder in [new.packages_dir.clone(), new.install_dir.clone()] {
+            if let Err(e) = fs::create_dir_all(&folder) {
+                main_log_error!("Could not make the packages folder {}: {e}", folder.display());
+                return new;
+            }
+        }
+
+        new.remove_marked();
+        new.install_waiting();
+        new.read_installed();
+        new
+    }
+
+    fn package_dirs(&self) -> Vec<PathBuf> {
+        match fs::read_dir(&self.packages_dir) {
+            Ok(entries) => entries.flatten().map(|entry| entry.path()).filter(|path| path.is_dir()).collect(),
+            Err(e) => {
+                main_log_error!("Could not read the packages folder: {e}");
+                Vec::new()
+            }
+        }
+    }
+
+    fn remove_marked(&self) {
+        for package_dir in self.package_dirs().into_iter().filter(|dir| dir.join(REMOVE_NAME).exists()) {
+            let id = package_dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+            match fs::remove_dir_all(&package_dir) {
+                Ok(()) => main_log!("Uninstalled package {id}"),
+                Err(e) => main_log_error!("Could not uninstall package {id}: {e}"),
+            }
+        }
+    }
+
+    fn install_waiting(&self) {
+        let Ok(entries) = fs::read_dir(&self.install_dir) else {
+            return;
+        };
+        let mut archives = entries.flatten().map(|entry| entry.path()).filter(|path| path.extension().is_some_and(|ext| ext == EXTENSION)).collect::<Vec<_>>();
+        archives.sort();
+
+        for archive in archives {
+            let file_name = archive.file_name().unwrap_or_default().to_string_lossy().to_string();
+            match install(&self.packages_dir, &archive) {
+                Ok((manifest, outcome)) => {
+                    main_notify!(Info, false, "Installed brick package", "{} v{} from {file_name}, {outcome}", manifest.name, manifest.version);
+                    if let Err(e) = fs::remove_file(&archive) {
+                        main_log_warning!("Could not remove {file_name} after installing it: {e}");
+                    }
+                }
+                Err(e) => {
+                    main_notify!(Error, true, "Could not install brick package", "{file_name}: {e}");
+                    // renamed so it isn't tried again every start
+                    let _ = fs::rename(&archive, archive.with_extension(format!("{EXTENSION}.failed")));
+                }
+            }
+        }
+    }
+
+    fn read_installed(&mut self) {
+        for package_dir in self.package_dirs() {
+            match Package::read(&package_dir) {
+                Ok(package) => {
+                    main_log_debug!("Found package {} v{}", package.manifest.id, package.manifest.version);
+                    self.installed.push(package);
+                }
+                Err(e) => main_log_warning!("Skipped the package in {}: {e}", package_dir.display()),
+            }
+        }
+        self.installed.sort_by_key(|package| package.manifest.name.to_lowercase());
+    }
+
+    /// The binaries of the installed packages of a kind, with the id of the package they came from
+    pub fn binaries(&self, kind: PackageKind) -> Vec<(String, PathBuf)> {
+        self.installed
+            .iter()
+            .filter(|package| package.manifest.kind == kind)
+            .filter_map(|package| package.binary().map(|binary| (package.manifest.id.clone(), binary)))
+            .collect()
+    }
+
+    pub fn draw(&mut self, ui: &Ui) {
+        ui.text_wrapped(format!("Put .{EXTENSION} files in {} to install or upgrade them the next time the game starts", self.install_dir.display()));
+        ui.separator();
+
+        if self.installed.is_empty() {
+            ui.text_disabled("No packages are installed");
+        }
+        for package in &mut self.installed {
+            package.draw(ui, &self.packages_dir);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = "id = \"cool-mod\"\nname = \"Cool Mod\"\nversion = \"1.2.0\"\n";
+
+    #[test]
+    fn enclosed_paths() {
+        for path in ["cool_mod.dll", "bin/linux-x86_64/libcool_mod.so", "./README.md", "docs/./LICENSE"] {
+            assert!(is_enclosed(path), "{path:?} should be enclosed");
+        }
+    }
+
+    #[test]
+    fn escaping_paths() {
+        for path in ["", "..", "../x", "bin/../../x", "/etc/passwd", "C:", "C:x", "C:/Windows/x.dll", "bin\\x.dll", "..\\x", "\\\\server\\share\\x"] {
+            assert!(!is_enclosed(path), "{path:?} should not be enclosed");
+        }
+    }
+
+    #[test]
+    fn parses_manifest() {
+        let text = format!("{MANIFEST}readme = \"docs/README.md\"\n[binaries]\nlinux-x86_64 = \"bin/libcool_mod.so\"\n");
+        let manifest = PackageManifest::parse(&text).unwrap();
+        assert_eq!(manifest.id, "cool-mod");
+        assert_eq!(manifest.kind, PackageKind::Mod);
+        assert_eq!(manifest.readme(), "docs/README.md");
+        assert_eq!(manifest.binaries["linux-x86_64"], "bin/libcool_mod.so");
+    }
+
+    #[test]
+    fn rejects_escaping_manifest_paths() {
+        for entry in [
+            "[binaries]\nlinux-x86_64 = \"../libcool_mod.so\"",
+            "[binaries]\nwindows-x86 = \"C:/cool_mod.dll\"",
+            "[binaries]\nwindows-x86 = \"bin\\\\cool_mod.dll\"",
+            "readme = \"/etc/passwd\"",
+            "license = \"../../LICENSE\"",
+        ] {
+            assert!(PackageManifest::parse(&format!("{MANIFEST}{entry}\n")).is_err(), "{entry:?} should be refused");
+        }
+    }
+
+    #[test]
+    fn rejects_bad_ids() {
+        for id in ["", ".hidden", "../x", "a/b", "a b"] {
+            let text = MANIFEST.replace("cool-mod", id);
+            assert!(PackageManifest::parse(&text).is_err(), "{id:?} should be refused");
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::{env, ffi::{CString, c_char}, path::{Path, PathBuf}, ptr, sync::OnceLock};
+
+pub(crate) use bluebrick::paths::PathKind;
+use bluebrick_proxy::Config;
+
+/// Every folder the loader uses, resolved once from the config so nothing depends on the working directory
+pub(crate) struct Paths {
+    root: PathBuf,
+    data: PathBuf,
+    cache: PathBuf,
+    logs: PathBuf,
+    bricks: PathBuf,
+}
+
+static PATHS: OnceLock<Paths> = OnceLock::new();
+
+impl Paths {
+    fn resolve(config: Option<&Config>) -> Self {
+        let root = config.and_then(Config::root_dir).unwrap_or_else(loader_dir);
+        let pick = |dir: Option<PathBuf>, default: &str| dir.map(|dir| root.join(dir)).unwrap_or_else(|| root.join(default));
+
+        Self {
+            data: pick(config.and_then(Config::data_dir), "data"),
+            cache: pick(config.and_then(Config::cache_dir), "cache"),
+            logs: pick(config.and_then(Config::log_dir), "logs"),
+            bricks: config.and_then(Config::brick_dir).map(|dir| root.join(dir)).unwrap_or_else(|| root.clone()),
+            root,
+        }
+    }
+}
+
+/// The folder the loader was loaded from, falling back to `bluebrick` in the working directory
+pub(crate) fn loader_dir() -> PathBuf {
+    let own_address = loader_dir as *const () as usize;
+    crate::crash::module_at(own_address)
+        .and_then(|(path, _)| path.parent().map(PathBuf::from))
+        .unwrap_or_else(|| env::current_dir().unwrap_or_default().join("bluebrick"))
+}
+
+/// Resolves the folders from the config, has to run before anything asks for a path
+pub(crate) fn init(config: &Config) {
+    let _ = PATHS.set(Paths::resolve(Some(config)));
+}
+
+fn get() -> &'static Paths {
+    PATHS.get_or_init(|| Paths::resolve(None))
+}
+
+pub(crate) fn root() -> &'static Path {
+    &get().root
+}
+
+/// Where `config.toml` and `imgui.ini` are, which is the root since the proxy reads the config before the loader exists
+pub(crate) fn config_dir() -> &'static Path {
+    &get().root
+}
+
+pub(crate) fn data_dir() -> &'static Path {
+    &get().data
+}
+
+pub(crate) fn cache_dir() -> &'static Path {
+    &get().cache
+}
+
+pub(crate) fn log_dir() -> &'static Path {
+    &get().logs
+}
+
+/// Holds the `libraries` and `mods` folders
+pub(crate) fn brick_dir() -> &'static Path {
+    &get().bricks
+}
+
+pub(crate) fn crash_dir() -> PathBuf {
+    log_dir().join("crashes")
+}
+
+pub(crate) fn dir(kind: PathKind) -> &'static Path {
+    match kind {
+        PathKind::Root => root(),
+        PathKind::Config => config_dir(),
+        PathKind::Data => data_dir(),
+        PathKind::Cache => cache_dir(),
+        PathKind::Logs => log_dir(),
+    }
+}
+
+/// Lists every folder for the log, so it's clear where things went
+pub(crate) fn describe() -> String {
+    let paths = get();
+    format!(
+        "Root: {}, data: {}, cache: {}, logs: {}, bricks: {}",
+        paths.root.display(),
+        paths.data.display(),
+        paths.cache.display(),
+        paths.logs.display(),
+        paths.bricks.display()
+    )
+}
+
+pub(crate) extern "C" fn dir_impl(kind: u32) -> *const c_char {
+    static C_PATHS: OnceLock<[CString; PathKind::ALL.len()]> = OnceLock::new();
+    let c_paths = C_PATHS.get_or_init(|| PathKind::ALL.map(|kind| CString::new(dir(kind).to_string_lossy().replace('\0', "")).unwrap_or_default()));
+    match PathKind::from_u32(kind) {
+        Some(kind) => c_paths[kind as usize].as_ptr(),
+        None => ptr::null(),
+    }
+}
@@ -0,0 +1,88 @@
+//! Starts BlueBrick when it is put in `LD_PRELOAD`, for native Linux games and test hosts that have no proxy
+
+use std::{ffi::{CStr, c_void}, mem, ptr};
+
+use bluebrick_proxy::{Config, RequestedPlatform, RequestedRenderer};
+
+use crate::{BlueBrickHandle, paths};
+
+/// Run by the dynamic loader when the library is loaded, before the program's main.
+/// std isn't set up for the program yet, so it only uses libc and leaves starting BlueBrick to a thread.
+#[used]
+#[unsafe(link_section = ".init_array")]
+static PRELOAD: extern "C" fn() = preload;
+
+/// Everything after the last slash
+fn file_name(path: &[u8]) -> &[u8] {
+    match path.iter().rposition(|&byte| byte == b'/') {
+        Some(slash) => &path[slash + 1..],
+        None => path,
+    }
+}
+
+/// Whether this library is named in `LD_PRELOAD`, so being loaded any other way doesn't start it.
+/// If it is, it is taken out, so the programs the game starts don't get BlueBrick too.
+unsafe fn take_own_preload() -> bool {
+    let mut info: libc::Dl_info = unsafe { mem::zeroed() };
+    if unsafe { libc::dladdr(preload as *const c_void, &mut info) } == 0 || info.dli_fname.is_null() {
+        return false;
+    }
+    let own_name = file_name(unsafe { CStr::from_ptr(info.dli_fname) }.to_bytes());
+
+    let value = unsafe { libc::getenv(c"LD_PRELOAD".as_ptr()) };
+    if value.is_null() {
+        return false;
+    }
+    let value = unsafe { CStr::from_ptr(value) }.to_bytes();
+
+    // the entries can be split by spaces or colons
+    let entries = || value.split(|&byte| byte == b' ' || byte == b':').filter(|entry| !entry.is_empty());
+    if !entries().any(|entry| file_name(entry) == own_name) {
+        return false;
+    }
+
+    let kept = unsafe { libc::malloc(value.len() + 1) } as *mut u8;
+    if kept.is_null() {
+        return true;
+    }
+    let mut len = 0;
+    for entry in entries().filter(|entry| file_name(entry) != own_name) {
+        if len > 0 {
+            unsafe { *kept.add(len) = b':' };
+            len += 1;
+        }
+        unsafe { ptr::copy_nonoverlapping(entry.as_ptr(), kept.add(len), entry.len()) };
+        len += entry.len();
+    }
+    unsafe { *kept.add(len) = 0 };
+
+    unsafe {
+        match len {
+            0 => libc::unsetenv(c"LD_PRELOAD".as_ptr()),
+            _ => libc::setenv(c"LD_PRELOAD".as_ptr(), kept as *const _, 1),
+        };
+        libc::free(kept as *mut c_void);
+    }
+    true
+}
+
+extern "C" fn preload() {
+    if !unsafe { take_own_preload() } {
+        return;
+    }
+
+    let mut thread: libc::pthread_t = unsafe { mem::zeroed() };
+    if unsafe { libc::pthread_create(&mut thread, ptr::null(), start, ptr::null_mut()) } == 0 {
+        unsafe { libc::pthread_detach(thread) };
+    }
+}
+
+extern "C" fn start(_: *mut c_void) -> *mut c_void {
+    // there is no overlay backend for linux games yet, so it is only on if the config or environment asks for it
+    let config = Config {
+        overlay_enabled: false,
+        ..Config::new(RequestedPlatform::Auto, RequestedRenderer::Auto)
+    };
+    BlueBrickHandle::start(bluebrick_proxy::read_config(config, &paths::loader_dir()));
+    ptr::null_mut()
+}
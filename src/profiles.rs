@@ -0,0 +1,300 @@
+//! Mod profiles, named sets of which bricks are enabled, the order they load in and their menu settings.
+//!
+//! Each profile is a file in `profiles` in the config folder, named after the profile:
+//!
+//! ```toml
+//! [[library]]
+//! brick = "helpers.dll"
+//! enabled = true
+//!
+//! [[mod]]
+//! brick = "speedrun-timer"
+//! enabled = true
+//! toggles = { "3" = true }
+//! windows = { "1" = false }
+//! ```
+//!
+//! `brick` is the id of the package a brick was installed from, or the file name of a loose brick.
+//! `toggles` and `windows` are the brick's menu toggles and windows by their id.
+//! Bricks load in the order they are listed, then the bricks the profile doesn't list load after them, enabled.
+
+use std::{collections::BTreeMap, fs, path::{Path, PathBuf}};
+
+use bluebrick::imgui::Ui;
+use serde::{Deserialize, Serialize};
+
+use crate::logger::{main_log, main_log_error, main_log_warning};
+use crate::overlay::notifications::main_notify;
+
+const PROFILES_FOLDER: &str = "profiles";
+const EXTENSION: &str = "toml";
+/// Holds the name of the profile last switched to in the overlay
+const ACTIVE_NAME: &str = "active";
+
+/// One brick's state in a profile
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct SavedBrick {
+    pub brick: String,
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub toggles: BTreeMap<String, bool>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub windows: BTreeMap<String, bool>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Profile {
+    #[serde(default, rename = "library")]
+    pub libraries: Vec<SavedBrick>,
+    #[serde(default, rename = "mod")]
+    pub mods: Vec<SavedBrick>,
+}
+
+impl Profile {
+    fn read(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("could not read {}: {e}", path.display()))?;
+        toml::from_str(&text).map_err(|e| format!("could not parse {}: {}", path.display(), e.message()))
+    }
+
+    fn write(&self, path: &Path) -> Result<(), String> {
+        let text = toml::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(path, text).map_err(|e| format!("could not write {}: {e}", path.display()))
+    }
+}
+
+/// Where a brick goes in a profile's load order, bricks it doesn't list go last
+pub(crate) fn load_position(saved: &[SavedBrick], brick: &str) -> usize {
+    saved.iter().position(|saved| saved.brick == brick).unwrap_or(usize::MAX)
+}
+
+/// Profile names are file names, so they can't hold anything a file name can't
+fn check_name(name: &str) -> Result<&str, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(String::from("profiles need a name"));
+    }
+    if name.starts_with('.') || name.chars().any(|c| c.is_control() || "/\\:*?\"<>|".contains(c)) {
+        return Err(format!("{name:?} can't be used as a profile name"));
+    }
+    Ok(name)
+}
+
+/// What the user asked for in the profiles tab, which the brick manager carries out since it has the bricks
+pub(crate) enum ProfileAction {
+    Switch(String),
+    Save(String),
+}
+
+pub(crate) struct Profiles {
+    dir: PathBuf,
+    names: Vec<String>,
+    active: Option<String>,
+    new_name: String,
+    import_path: String,
+    export_path: String,
+}
+
+impl Profiles {
+    pub fn new(config_dir: &Path) -> Self {
+        let dir = config_dir.join(PROFILES_FOLDER);
+        if let Err(e) = fs::create_dir_all(&dir) {
+            main_log_error!("Could not make the profiles folder {}: {e}", dir.display());
+        }
+
+        let mut new = Self {
+            active: fs::read_to_string(dir.join(ACTIVE_NAME)).ok().map(|name| String::from(name.trim())).filter(|name| !name.is_empty()),
+            dir,
+            names: Vec::new(),
+            new_name: String::new(),
+            import_path: String::new(),
+            export_path: String::new(),
+        };
+        new.read_names();
+        new
+    }
+
+    fn read_names(&mut self) {
+        self.names = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == EXTENSION))
+                .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().to_string()))
+                .collect(),
+            Err(e) => {
+                main_log_error!("Could not read the profiles folder: {e}");
+                Vec::new()
+            }
+        };
+        self.names.sort_by_key(|name| name.to_lowercase());
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.{EXTENSION}"))
+    }
+
+    /// The profile to start with, `requested` by the config if it is set, or else the one last switched to
+    pub fn starting(&mut self, requested: Option<String>) -> Profile {
+        if let Some(requested) = requested {
+            if self.names.contains(&requested) {
+                self.active = Some(requested);
+            } else {
+                main_notify!(Warning, true, "Unknown profile", "The config asks for the profile {requested}, which doesn't exist in {}", self.dir.display());
+            }
+        }
+
+        let Some(name) = self.active.clone() else {
+            return Profile::default();
+        };
+        match self.read(&name) {
+            Ok(profile) => {
+                main_log!("Starting with the profile {name}");
+                profile
+            }
+            Err(e) => {
+                main_notify!(Error, true, "Could not load profile", "{name}: {e}");
+                self.active = None;
+                Profile::default()
+            }
+        }
+    }
+
+    pub fn read(&self, name: &str) -> Result<Profile, String> {
+        Profile::read(&self.path(name))
+    }
+
+    pub fn save(&mut self, name: &str, profile: &Profile) -> Result<(), String> {
+        let name = check_name(name)?;
+        profile.write(&self.path(name))?;
+        if !self.names.iter().any(|known| known == name) {
+            self.read_names();
+        }
+        Ok(())
+    }
+
+    /// Remembers `name` as the profile to start with next time
+    pub fn set_active(&mut self, name: &str) {
+        if let Err(e) = fs::write(self.dir.join(ACTIVE_NAME), name) {
+            main_log_warning!("Could not remember the profile {name} for the next start: {e}");
+        }
+        self.active = Some(String::from(name));
+    }
+
+    fn delete(&mut self, name: &str) -> Result<(), String> {
+        fs::remove_file(self.path(name)).map_err(|e| e.to_string())?;
+        if self.active.as_deref() == Some(name) {
+            let _ = fs::remove_file(self.dir.join(ACTIVE_NAME));
+            self.active = None;
+        }
+        self.read_names();
+        Ok(())
+    }
+
+    /// Copies a profile file into the profiles folder, named after the file
+    fn import(&mut self, path: &Path) -> Result<String, String> {
+        let profile = Profile::read(path)?;
+        let name = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+        let name = check_name(&name)?.to_string();
+        if self.names.contains(&name) {
+            main_log_warning!("Replacing the profile {name} with the one imported from {}", path.display());
+        }
+        self.save(&name, &profile)?;
+        Ok(name)
+    }
+
+    /// Writes a profile to `path`, or into it named after the profile if it is a folder
+    fn export(&self, name: &str, path: &Path) -> Result<PathBuf, String> {
+        let out = match path.is_dir() {
+            true => path.join(format!("{name}.{EXTENSION}")),
+            false => path.to_path_buf(),
+        };
+        fs::copy(self.path(name), &out).map_err(|e| format!("could not write {}: {e}", out.display()))?;
+        Ok(out)
+    }
+
+    pub fn draw(&mut self, ui: &Ui) -> Option<ProfileAction> {
+        let mut action = None;
+
+        ui.text_wrapped(format!("Profiles are kept in {}", self.dir.display()));
+        match &self.active {
+            Some(active) => ui.text(format!("Active profile: {active}")),
+            None => ui.text_disabled("No profile is active"),
+        }
+        ui.separator();
+
+        if self.names.is_empty() {
+            ui.text_disabled("There are no profiles yet, save the current bricks as one below");
+        }
+        let mut deleted = None;
+        for name in &self.names {
+            let _id = ui.push_id(name);
+            ui.text(name);
+            ui.same_line();
+            ui.disabled(self.active.as_ref() == Some(name), || {
+                if ui.small_button("Switch") {
+                    action = Some(ProfileAction::Switch(name.clone()));
+                }
+            });
+            ui.same_line();
+            if ui.small_button("Save current") {
+                action = Some(ProfileAction::Save(name.clone()));
+            }
+            ui.same_line();
+            ui.disabled(self.export_path.trim().is_empty(), || {
+                if ui.small_button("Export") {
+                    match self.export(name, Path::new(self.export_path.trim())) {
+                        Ok(out) => main_log!("Exported the profile {name} to {}", out.display()),
+                        Err(e) => {
+                            main_notify!(Error, false, "Could not export profile", "{name}: {e}");
+                        }
+                    }
+                }
+            });
+            ui.same_line();
+            if ui.small_button("Delete") {
+                deleted = Some(name.clone());
+            }
+        }
+        if let Some(name) = deleted
+            && let Err(e) = self.delete(&name)
+        {
+            main_notify!(Error, false, "Could not delete profile", "{name}: {e}");
+        }
+
+        ui.separator();
+        ui.input_text("##ProfileName", &mut self.new_name).hint("New profile name").build();
+        ui.same_line();
+        if ui.button("Save current as") {
+            match check_name(&self.new_name) {
+                Ok(name) => {
+                    action = Some(ProfileAction::Save(String::from(name)));
+                    self.new_name.clear();
+                }
+                Err(e) => {
+                    main_notify!(Warning, false, "Could not save profile", "{e}");
+                }
+            }
+        }
+
+        ui.input_text("##ProfileImport", &mut self.import_path).hint("Profile file to import").build();
+        ui.same_line();
+        if ui.button("Import") {
+            let path = PathBuf::from(self.import_path.trim());
+            match self.import(&path) {
+                Ok(name) => {
+                    main_log!("Imported the profile {name}");
+                    self.import_path.clear();
+                }
+                Err(e) => {
+                    main_notify!(Error, false, "Could not import profile", "{e}");
+                }
+            }
+        }
+
+        ui.input_text("##ProfileExport", &mut self.export_path).hint("File or folder to export profiles to").build();
+
+        action
+    }
+}
@@ -1,13 +1,23 @@
-use std::{ffi::{CStr, OsStr, c_char, c_void}, fs::{self, DirEntry}};
+use std::{collections::BTreeMap, ffi::{CStr, OsStr, c_char, c_void}, fs, path::{Path, PathBuf}};
 
 use dlopen::wrapper::{Container, WrapperApi};
 use dlopen_derive::WrapperApi;
-use bluebrick::{imgui::{self, Ui, sys::ImGuiContext}};
+use bluebrick::{host::HostApi, imgui::{self, Direction, Ui, sys::ImGuiContext}, menu::{BrickMenu, MenuBuilder, MenuEntry, MenuEntryKind, MenuId}};
 
-use crate::logger::{main_log_debug, main_log_error, main_log_warning};
+use crate::host::HOST_API;
+use crate::logger::{main_log, main_log_debug, main_log_error, main_log_warning};
+use crate::overlay::notifications::main_notify;
+use crate::packages::{PackageKind, Packages};
+use crate::profiles::{Profile, ProfileAction, Profiles, SavedBrick, load_position};
+use crate::trust::{BrickFile, Trust, TrustStatus};
 
-fn get_file_name(entry: &DirEntry) -> String {
-    entry.file_name().to_string_lossy().to_string()
+fn get_file_name(path: &Path) -> String {
+    path.file_name().unwrap_or_default().to_string_lossy().to_string()
+}
+
+/// What profiles know a brick by, the id of its package or the file name of a loose brick
+fn brick_key(package: &Option<String>, file: &Path) -> String {
+    package.clone().unwrap_or_else(|| get_file_name(file))
 }
 
 #[derive(WrapperApi)]
@@ -16,6 +26,7 @@ pub(crate) struct SubBrickApi {
     author: extern "C" fn() -> *const c_char,
     version: extern "C" fn() -> *const c_char,
 
+    set_host_api: extern "C" fn(api: &'static HostApi) -> bool,
     new: extern "C" fn() -> *mut c_void,
     init: extern "C" fn(subbrick: *mut c_void),
     enable: extern "C" fn(subbrick: *mut c_void) -> bool,
@@ -23,22 +34,34 @@ pub(crate) struct SubBrickApi {
 
     set_imgui_ctx: extern "C" fn(ctx: *mut ImGuiContext),
     draw: extern "C" fn(subbrick: *mut c_void, ui: &Ui),
+
+    build_menu: extern "C" fn(subbrick: *mut c_void, builder: &MenuBuilder),
+    menu_clicked: extern "C" fn(subbrick: *mut c_void, id: MenuId, checked: bool),
+    draw_window: extern "C" fn(subbrick: *mut c_void, id: MenuId, ui: &Ui, opened: &mut bool),
 }
 
 pub(crate) struct SubBrick {
     ptr: *mut c_void,
     api: Container<SubBrickApi>,
-    file: DirEntry,
+    /// Held until after the brick is unloaded
+    file: BrickFile,
+    /// The id of the package the brick was installed from, if it wasn't dropped in loose
+    package: Option<String>,
+    trust: TrustStatus,
     enabled: bool,
+    menu: BrickMenu,
 }
 
 impl SubBrick {
-    fn new(api: Container<SubBrickApi>, file: DirEntry) -> Self {
+    fn new(api: Container<SubBrickApi>, file: BrickFile, package: Option<String>, trust: TrustStatus) -> Self {
         Self {
             ptr: api.new(),
             api,
             file,
+            package,
+            trust,
             enabled: false,
+            menu: BrickMenu::new(),
         }
     }
 
@@ -50,10 +73,12 @@ impl SubBrick {
     fn name(&self) -> String { Self::to_string((self.api.name)()) }
     fn author(&self) -> String { Self::to_string((self.api.author)()) }
     fn version(&self) -> String { Self::to_string((self.api.version)()) }
-    fn file_name(&self) -> String { get_file_name(&self.file) }
+    fn file_name(&self) -> String { get_file_name(self.file.path()) }
+    fn key(&self) -> String { brick_key(&self.package, self.file.path()) }
     
-    fn init(&self) {
+    fn init(&mut self) {
         (self.api.init)(self.ptr);
+        self.menu = BrickMenu::receive(|builder| (self.api.build_menu)(self.ptr, builder));
         main_log_debug!("Loaded {}", self.string_info());
     }
     fn enable(&mut self) -> bool {
@@ -62,7 +87,7 @@ impl SubBrick {
             self.enabled = true;
             main_log_debug!("Enabled {}", self.string_info());
         } else {
-            main_log_warning!("Failed to enable {}", self.string_info());
+            main_notify!(Warning, true, "Failed to enable brick", "{}", self.string_info());
         }
         result
     }
@@ -79,17 +104,136 @@ impl SubBrick {
 
     fn set_imgui_ctx(&self, ctx: *mut ImGuiContext) { (self.api.set_imgui_ctx)(ctx) }
     fn draw(&self, ui: &Ui) { (self.api.draw)(self.ptr, ui) }
+    fn menu_clicked(&self, id: MenuId, checked: bool) { (self.api.menu_clicked)(self.ptr, id, checked) }
+
+    fn draw_windows(&mut self, ui: &Ui) {
+        let ptr = self.ptr;
+        let draw_window = self.api.draw_window;
+        self.menu.for_each_window(&mut |id, open| {
+            if *open {
+                draw_window(ptr, id, ui, open);
+            }
+        });
+    }
+
+    fn draw_menu(&mut self, ui: &Ui) {
+        if self.menu.is_empty() {
+            return;
+        }
+
+        let mut clicked = Vec::new();
+        ui.menu_with_enabled(self.name(), self.enabled, || {
+            Self::draw_menu_entries(ui, &mut self.menu, &mut clicked);
+        });
+
+        for (id, checked) in clicked {
+            self.menu_clicked(id, checked);
+        }
+    }
+
+    fn draw_menu_entries(ui: &Ui, menu: &mut BrickMenu, clicked: &mut Vec<(MenuId, bool)>) {
+        for entry in &mut menu.entries {
+            let shortcut = entry.shortcut.as_deref().unwrap_or_default();
+            match &mut entry.kind {
+                MenuEntryKind::Item => {
+                    if ui.menu_item_config(&entry.label).shortcut(shortcut).build() && let Some(id) = entry.id {
+                        clicked.push((id, false));
+                    }
+                }
+                MenuEntryKind::Toggle { checked } => {
+                    if ui.menu_item_config(&entry.label).shortcut(shortcut).build_with_ref(checked) && let Some(id) = entry.id {
+                        clicked.push((id, *checked));
+                    }
+                }
+                // window state is only reported through draw_window
+                MenuEntryKind::Window { open } => {
+                    ui.menu_item_config(&entry.label).shortcut(shortcut).build_with_ref(open);
+                }
+                MenuEntryKind::Separator => ui.separator(),
+                MenuEntryKind::SubMenu(submenu) => {
+                    ui.menu(&entry.label, || Self::draw_menu_entries(ui, submenu, clicked));
+                }
+            }
+        }
+    }
+
+    /// The brick's enabled state and menu settings, for saving to a profile
+    fn save(&self) -> SavedBrick {
+        let mut saved = SavedBrick {
+            brick: self.key(),
+            enabled: self.enabled,
+            toggles: BTreeMap::new(),
+            windows: BTreeMap::new(),
+        };
+        Self::save_menu(&self.menu, &mut saved);
+        saved
+    }
+
+    fn save_menu(menu: &BrickMenu, saved: &mut SavedBrick) {
+        for entry in &menu.entries {
+            match (&entry.kind, entry.id) {
+                (MenuEntryKind::Toggle { checked }, Some(id)) => _ = saved.toggles.insert(id.to_string(), *checked),
+                (MenuEntryKind::Window { open }, Some(id)) => _ = saved.windows.insert(id.to_string(), *open),
+                (MenuEntryKind::SubMenu(submenu), _) => Self::save_menu(submenu, saved),
+                _ => {}
+            }
+        }
+    }
+
+    /// Sets the brick up like `saved` says, telling it about each toggle that changes.
+    /// Bricks a profile doesn't have are enabled.
+    fn apply_profile(&mut self, saved: &[SavedBrick]) {
+        let key = self.key();
+        let saved = saved.iter().find(|saved| saved.brick == key);
+
+        if let Some(saved) = saved {
+            for (id, checked) in &saved.toggles {
+                let Ok(id) = id.parse::<MenuId>() else { continue };
+                let changed = match self.menu.find_mut(id) {
+                    Some(MenuEntry { kind: MenuEntryKind::Toggle { checked: current }, .. }) if current != checked => {
+                        *current = *checked;
+                        true
+                    }
+                    _ => false,
+                };
+                if changed {
+                    self.menu_clicked(id, *checked);
+                }
+            }
+            for (id, open) in &saved.windows {
+                if let Ok(id) = id.parse::<MenuId>()
+                    && let Some(MenuEntry { kind: MenuEntryKind::Window { open: current }, .. }) = self.menu.find_mut(id)
+                {
+                    *current = *open;
+                }
+            }
+        }
+
+        let enable = saved.is_none_or(|saved| saved.enabled);
+        if enable && !self.enabled {
+            self.enable();
+        } else if !enable && self.enabled {
+            self.disable();
+        }
+    }
 
     fn string_info(&self) -> String {
-        format!("{} v{} by {} from {}", self.name(), self.version(), self.author(), self.file_name())
+        match &self.package {
+            Some(package) => format!("{} v{} by {} from package {package}", self.name(), self.version(), self.author()),
+            None => format!("{} v{} by {} from {}", self.name(), self.version(), self.author(), self.file_name()),
+        }
     }
 
-    fn draw_brick_list_item(&mut self, ui: &Ui) {
+    fn draw_brick_list_item(&mut self, ui: &Ui, trust: &mut Trust) {
         ui.group(|| {
             ui.text(format!("{} v{}", self.name(), self.version()));
             ui.text(format!("by {}", self.author()));
             ui.same_line();
-            ui.text_colored([0.5, 0.5, 0.5, 1.0], format!("from {}", self.file_name()));
+            match &self.package {
+                Some(package) => ui.text_colored([0.5, 0.5, 0.5, 1.0], format!("from package {package}")),
+                None => ui.text_colored([0.5, 0.5, 0.5, 1.0], format!("from {}", self.file_name())),
+            }
+            trust.draw_status(ui, &self.trust);
         });
         
         ui.same_line();
@@ -120,28 +264,77 @@ impl SubBrick {
     }
 }
 
+/// A brick that wasn't loaded because of its signature or safe mode, kept to show why in the overlay
+pub(crate) struct BlockedBrick {
+    file: PathBuf,
+    package: Option<String>,
+    trust: TrustStatus,
+}
+
+impl BlockedBrick {
+    fn draw_brick_list_item(&self, ui: &Ui, trust: &mut Trust) {
+        ui.group(|| {
+            let name = self.package.clone().unwrap_or_else(|| get_file_name(&self.file));
+            ui.text_colored([1.0, 0.3, 0.3, 1.0], format!("{name} was not loaded"));
+            trust.draw_status(ui, &self.trust);
+        });
+    }
+}
+
 pub(crate) struct SubBrickManager {
     libraries: Vec<SubBrick>,
     mods: Vec<SubBrick>,
+    blocked_libraries: Vec<BlockedBrick>,
+    blocked_mods: Vec<BlockedBrick>,
+    packages: Packages,
+    trust: Trust,
+    profiles: Profiles,
+    /// Nothing was loaded, so there is nothing to save to a profile
+    safe_mode: bool,
 }
 
 impl SubBrickManager {
-    pub fn new() -> Self {
+    /// Bricks are loaded and set up in the order of the `profile` the config asks for, or the one last switched to.
+    /// In `safe_mode` none of their code runs, the files are only listed.
+    pub fn new(brick_dir: &Path, safe_mode: bool, trust: Trust, mut profiles: Profiles, profile: Option<String>) -> Self {
+        let profile = profiles.starting(profile);
         let mut new = Self {
             libraries: Vec::new(),
-            mods: Vec::new()
+            mods: Vec::new(),
+            blocked_libraries: Vec::new(),
+            blocked_mods: Vec::new(),
+            packages: Packages::new(brick_dir),
+            trust,
+            profiles,
+            safe_mode,
         };
 
         main_log_debug!("Loading Libraries:");
-        Self::load_subbricks("libraries", "bluebrick/libraries", &mut new.libraries);
+        new.load_subbricks(PackageKind::Library, brick_dir, &profile.libraries);
 
         main_log_debug!("Loading Mods:");
-        Self::load_subbricks("mods", "bluebrick/mods", &mut new.mods);
+        new.load_subbricks(PackageKind::Mod, brick_dir, &profile.mods);
 
         new
     }
 
-    fn load_subbricks(kind: &str, folder: &str, subbricks: &mut Vec<SubBrick>) {
+    /// Loads the loose files in the kind's folder, then the binaries of the installed packages of the same kind,
+    /// skipping the ones whose signature `trust` doesn't allow, in the order `saved` lists them
+    /// The functions a brick that failed to load doesn't export, None if it has them all or its exports can't be read
+    fn missing_exports(file: &BrickFile) -> Option<Vec<&'static str>> {
+        let exports = bluebrick_exports::read_exports(&file.read().ok()?).ok()??;
+        Some(exports.missing()).filter(|missing| !missing.is_empty())
+    }
+
+    fn load_subbricks(&mut self, kind: PackageKind, brick_dir: &Path, saved: &[SavedBrick]) {
+        let packaged = self.packages.binaries(kind);
+        let trust = &self.trust;
+        let (kind, subbricks, blocked) = match kind {
+            PackageKind::Library => ("libraries", &mut self.libraries, &mut self.blocked_libraries),
+            PackageKind::Mod => ("mods", &mut self.mods, &mut self.blocked_mods),
+        };
+        let folder = &brick_dir.join(kind);
+
         match fs::exists(folder) {
             Ok(true) => {}
             Ok(false) => {
@@ -167,12 +360,12 @@ impl SubBrickManager {
                 let file_type = match entry.file_type() {
                     Ok(file_type) => file_type,
                     Err(e) => {
-                        main_log_warning!("Unable to read file type of {} in BlueBrick {} folder: {}", get_file_name(&entry), kind, e);
+                        main_log_warning!("Unable to read file type of {} in BlueBrick {} folder: {}", get_file_name(&entry.path()), kind, e);
                         return None;
                     }
                 };
-                if file_type.is_file() && ["dll", "so", "dylib"].map(|s| OsStr::new(s)).contains(&entry.path().extension().unwrap_or_default()) {
-                    Some(entry)
+                if file_type.is_file() && ["dll", "so", "dylib"].map(OsStr::new).contains(&entry.path().extension().unwrap_or_default()) {
+                    Some((None, entry.path()))
                 } else {
                     None
                 }
@@ -183,37 +376,191 @@ impl SubBrickManager {
             }
         });
 
-        for entry in entries {
-            let mut subbrick = SubBrick::new(match unsafe { Container::<SubBrickApi>::load(entry.path()) } {
+        let mut entries = entries.chain(packaged.into_iter().map(|(package, file)| (Some(package), file))).collect::<Vec<_>>();
+        entries.sort_by_key(|(package, file)| load_position(saved, &brick_key(package, file)));
+
+        for (package, file) in entries {
+            let source = match &package {
+                Some(package) => format!("{} from package {package}", get_file_name(&file)),
+                None => format!("{} in BlueBrick {kind} folder", get_file_name(&file)),
+            };
+
+            let brick_file = match BrickFile::open(&file) {
+                Ok(brick_file) => brick_file,
+                Err(e) => {
+                    main_log_warning!("Unable to load {source}: {e}");
+                    continue;
+                }
+            };
+
+            let status = trust.check(&brick_file);
+            if !trust.allows(&status, &source) {
+                match status {
+                    TrustStatus::Invalid(_) => {
+                        main_notify!(Error, true, "Blocked brick", "Did not load {source}, it has an {status}");
+                    }
+                    _ => {
+                        main_notify!(Warning, true, "Blocked brick", "Did not load {source}, it is {status}");
+                    }
+                }
+                blocked.push(BlockedBrick { file, package, trust: status });
+                continue;
+            }
+
+            if self.safe_mode {
+                main_log_debug!("Skipped {source} in safe mode");
+                blocked.push(BlockedBrick { file, package, trust: status });
+                continue;
+            }
+
+            // loaded through the file that was checked, so it can't be replaced after its signature was
+            let api = match unsafe { Container::<SubBrickApi>::load(brick_file.load_path()) } {
                 Ok(library) => library,
                 Err(e) => {
-                    main_log_warning!("Unable to load {} in BlueBrick {kind} folder: {}", get_file_name(&entry), e);
+                    match Self::missing_exports(&brick_file) {
+                        Some(missing) => main_log_warning!("Unable to load {source}, it is missing {}. Was it built with #[bluebrick_mod] or #[bluebrick_library]?", missing.join(", ")),
+                        None => main_log_warning!("Unable to load {source}: {e}"),
+                    }
                     continue;
                 }
-            }, entry);
+            };
+
+            if !api.set_host_api(&HOST_API) {
+                main_log_warning!("Unable to load {source}: it needs a newer version of BlueBrick");
+                continue;
+            }
+
+            let mut subbrick = SubBrick::new(api, brick_file, package, status);
 
             subbrick.set_imgui_ctx(unsafe { imgui::sys::igGetCurrentContext() });
             subbrick.init();
-            subbrick.enable();
+            subbrick.apply_profile(saved);
             subbricks.push(subbrick);
         }
     }
 
-    pub fn draw_all(&self, ui: &Ui) {
-        for subbrick in self.libraries.iter().chain(self.mods.iter()) {
+    pub fn brick_infos(&self) -> Vec<String> {
+        let libraries = self.libraries.iter().map(|library| format!("Library: {}, {}", library.string_info(), library.trust));
+        let mods = self.mods.iter().map(|mmod| format!("Mod: {}, {}", mmod.string_info(), mmod.trust));
+        libraries.chain(mods).collect()
+    }
+
+    pub fn draw_all(&mut self, ui: &Ui) {
+        for subbrick in self.libraries.iter_mut().chain(self.mods.iter_mut()) {
             subbrick.draw(ui);
+            if subbrick.enabled {
+                subbrick.draw_windows(ui);
+            }
         }
     }
 
-    pub fn draw_library_list(&mut self, ui: &Ui) {
+    pub fn draw_bricks_menu(&mut self, ui: &Ui) {
+        if self.libraries.iter().chain(self.mods.iter()).all(|subbrick| subbrick.menu.is_empty()) {
+            ui.text_disabled("No bricks have menu entries");
+            return;
+        }
+
         for library in &mut self.libraries {
-            library.draw_brick_list_item(ui);
+            library.draw_menu(ui);
+        }
+
+        if self.libraries.iter().any(|library| !library.menu.is_empty()) && self.mods.iter().any(|mmod| !mmod.menu.is_empty()) {
+            ui.separator();
+        }
+
+        for mmod in &mut self.mods {
+            mmod.draw_menu(ui);
         }
     }
 
+    /// Draws the loaded bricks with arrows to change their load order, then the ones that were blocked
+    fn draw_brick_list(ui: &Ui, subbricks: &mut [SubBrick], blocked: &[BlockedBrick], trust: &mut Trust) {
+        let mut moved = None;
+        let count = subbricks.len();
+        for (i, subbrick) in subbricks.iter_mut().enumerate() {
+            let _id = ui.push_id_usize(i);
+            ui.group(|| {
+                ui.disabled(i == 0, || {
+                    if ui.arrow_button("##Up", Direction::Up) {
+                        moved = Some((i, i - 1));
+                    }
+                });
+                ui.disabled(i + 1 == count, || {
+                    if ui.arrow_button("##Down", Direction::Down) {
+                        moved = Some((i, i + 1));
+                    }
+                });
+            });
+            if ui.is_item_hovered() {
+                ui.tooltip_text("Load order, kept by saving it to a profile and used from the next start");
+            }
+            ui.same_line();
+            subbrick.draw_brick_list_item(ui, trust);
+        }
+        if let Some((from, to)) = moved {
+            subbricks.swap(from, to);
+        }
+
+        for brick in blocked {
+            brick.draw_brick_list_item(ui, trust);
+        }
+    }
+
+    pub fn draw_library_list(&mut self, ui: &Ui) {
+        Self::draw_brick_list(ui, &mut self.libraries, &self.blocked_libraries, &mut self.trust);
+    }
+
+    pub fn draw_package_list(&mut self, ui: &Ui) {
+        self.packages.draw(ui);
+    }
+
     pub fn draw_mod_list(&mut self, ui: &Ui) {
-        for mmod in &mut self.mods {
-            mmod.draw_brick_list_item(ui);
+        Self::draw_brick_list(ui, &mut self.mods, &self.blocked_mods, &mut self.trust);
+    }
+
+    fn save_profile(&self) -> Profile {
+        Profile {
+            libraries: self.libraries.iter().map(SubBrick::save).collect(),
+            mods: self.mods.iter().map(SubBrick::save).collect(),
+        }
+    }
+
+    /// Enables, disables and reorders the loaded bricks like the game had started with the profile
+    fn switch_profile(&mut self, name: &str) {
+        let profile = match self.profiles.read(name) {
+            Ok(profile) => profile,
+            Err(e) => {
+                main_notify!(Error, false, "Could not switch profile", "{name}: {e}");
+                return;
+            }
+        };
+
+        for (subbricks, saved) in [(&mut self.libraries, &profile.libraries), (&mut self.mods, &profile.mods)] {
+            subbricks.sort_by_key(|subbrick| load_position(saved, &subbrick.key()));
+            for subbrick in subbricks.iter_mut() {
+                subbrick.apply_profile(saved);
+            }
+        }
+        self.profiles.set_active(name);
+        main_notify!(Info, false, "Switched profile", "Switched to {name}");
+    }
+
+    pub fn draw_profile_list(&mut self, ui: &Ui) {
+        match self.profiles.draw(ui) {
+            Some(ProfileAction::Switch(name)) => self.switch_profile(&name),
+            Some(ProfileAction::Save(name)) if self.safe_mode => {
+                main_notify!(Warning, false, "Could not save profile", "{name}: no bricks are loaded in safe mode");
+            }
+            Some(ProfileAction::Save(name)) => match self.profiles.save(&name, &self.save_profile()) {
+                Ok(()) => {
+                    self.profiles.set_active(&name);
+                    main_log!("Saved the bricks to the profile {name}");
+                }
+                Err(e) => {
+                    main_notify!(Error, false, "Could not save profile", "{name}: {e}");
+                }
+            },
+            None => {}
         }
     }
 }
@@ -0,0 +1,275 @@
+//! Checks a brick's binary against its signature and the authors the player trusts, before it is loaded.
+//!
+//! A signature is a `.sig` file next to the binary, `cool_mod.dll.sig` for `cool_mod.dll`, which
+//! `cargo bluebrick sign` writes:
+//!
+//! ```toml
+//! sha256 = "<sha-256 of the binary, as hex>"
+//! key = "<the author's ed25519 public key, as hex>"
+//! signature = "<the ed25519 signature of the sha-256, as hex>"
+//! ```
+//!
+//! Keys are trusted by listing them in `trusted_keys.toml` in the config folder:
+//!
+//! ```toml
+//! [[key]]
+//! name = "Someone"
+//! key = "<public key, as hex>"
+//! ```
+
+use std::{fmt, fs::{self, File, OpenOptions}, io::{self, Read, Seek, SeekFrom}, path::{Path, PathBuf}};
+
+use bluebrick::imgui::Ui;
+use bluebrick_proxy::UnsignedBricks;
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::logger::{main_log_debug, main_log_error, main_log_warning};
+
+const TRUSTED_KEYS_NAME: &str = "trusted_keys.toml";
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SignatureFile {
+    sha256: String,
+    key: String,
+    signature: String,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+struct TrustedKey {
+    name: String,
+    key: String,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
+struct TrustedKeys {
+    #[serde(default, rename = "key")]
+    keys: Vec<TrustedKey>,
+}
+
+/// What the signature next to a brick says about it
+#[derive(Clone)]
+pub(crate) enum TrustStatus {
+    /// Signed by a trusted key, holds the name it was trusted under
+    Verified(String),
+    Unsigned,
+    /// Signed, but by a key that isn't trusted, holds the key as hex
+    UntrustedKey(String),
+    /// The signature doesn't match the binary or can't be read, so the binary was changed or the signature is broken
+    Invalid(String),
+}
+
+impl TrustStatus {
+    fn color(&self) -> [f32; 4] {
+        match self {
+            TrustStatus::Verified(_) => [0.4, 0.9, 0.4, 1.0],
+            TrustStatus::Unsigned | TrustStatus::UntrustedKey(_) => [1.0, 0.6, 0.2, 1.0],
+            TrustStatus::Invalid(_) => [1.0, 0.3, 0.3, 1.0],
+        }
+    }
+}
+
+impl fmt::Display for TrustStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TrustStatus::Verified(name) => write!(f, "signed by {name}"),
+            TrustStatus::Unsigned => write!(f, "unsigned"),
+            TrustStatus::UntrustedKey(key) => write!(f, "signed by an untrusted key {}", &key[..key.len().min(16)]),
+            TrustStatus::Invalid(reason) => write!(f, "invalid signature, {reason}"),
+        }
+    }
+}
+
+fn parse_hex<const N: usize>(hex: &str, what: &str) -> Result<[u8; N], String> {
+    hex::decode(hex.trim())
+        .ok()
+        .and_then(|bytes| <[u8; N]>::try_from(bytes).ok())
+        .ok_or_else(|| format!("its {what} isn't {N} bytes of hex"))
+}
+
+/// A brick's binary, held open from its signature check until it is unloaded, so another file can't be swapped in between
+pub(crate) struct BrickFile {
+    file: File,
+    path: PathBuf,
+}
+
+impl BrickFile {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let mut options = OpenOptions::new();
+        options.read(true);
+        // only FILE_SHARE_READ, so the file can't be written, renamed or deleted while it is held
+        #[cfg(windows)]
+        std::os::windows::fs::OpenOptionsExt::share_mode(&mut options, 0x1);
+        Ok(Self { file: options.open(path)?, path: path.to_path_buf() })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// What to hand the dynamic loader, on linux a path to the held file itself rather than whatever is at `path` now
+    pub fn load_path(&self) -> PathBuf {
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::fd::AsRawFd;
+            PathBuf::from(format!("/proc/self/fd/{}", self.file.as_raw_fd()))
+        }
+        #[cfg(not(target_os = "linux"))]
+        self.path.clone()
+    }
+
+    pub fn read(&self) -> io::Result<Vec<u8>> {
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start(0))?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+pub(crate) struct Trust {
+    policy: UnsignedBricks,
+    keys: Vec<TrustedKey>,
+    keys_path: PathBuf,
+    /// The untrusted key the player is naming, and the name so far
+    naming: Option<(String, String)>,
+}
+
+impl Trust {
+    pub fn new(policy: UnsignedBricks, config_dir: &Path) -> Self {
+        let keys_path = config_dir.join(TRUSTED_KEYS_NAME);
+        let keys = match fs::read_to_string(&keys_path) {
+            Ok(text) => match toml::from_str::<TrustedKeys>(&text) {
+                Ok(keys) => keys.keys,
+                Err(e) => {
+                    main_log_error!("Could not parse {}, no brick authors are trusted: {}", keys_path.display(), e.message());
+                    Vec::new()
+                }
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                main_log_error!("Could not read {}, no brick authors are trusted: {e}", keys_path.display());
+                Vec::new()
+            }
+        };
+        main_log_debug!("Trusting {} brick signing keys, unsigned bricks are set to {policy:?}", keys.len());
+
+        Self { policy, keys, keys_path, naming: None }
+    }
+
+    fn trusted_name(&self, key: &str) -> Option<&str> {
+        self.keys.iter().find(|trusted| trusted.key.trim().eq_ignore_ascii_case(key)).map(|trusted| trusted.name.as_str())
+    }
+
+    /// Hashes `binary` and checks it against the signature next to it
+    pub fn check(&self, binary: &BrickFile) -> TrustStatus {
+        let mut signature_path = binary.path().as_os_str().to_os_string();
+        signature_path.push(".sig");
+        let text = match fs::read_to_string(PathBuf::from(signature_path)) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return TrustStatus::Unsigned,
+            Err(e) => return TrustStatus::Invalid(format!("its signature could not be read: {e}")),
+        };
+
+        let verify = || -> Result<TrustStatus, String> {
+            let file: SignatureFile = toml::from_str(&text).map_err(|e| format!("its signature could not be parsed: {}", e.message()))?;
+            let hash = parse_hex::<32>(&file.sha256, "hash")?;
+            let key = VerifyingKey::from_bytes(&parse_hex::<32>(&file.key, "key")?).map_err(|_| String::from("its key isn't an ed25519 key"))?;
+            let signature = Signature::from_bytes(&parse_hex::<64>(&file.signature, "signature")?);
+
+            let bytes = binary.read().map_err(|e| format!("the brick could not be read: {e}"))?;
+            if Sha256::digest(&bytes).as_slice() != hash {
+                return Err(String::from("the brick was changed after it was signed"));
+            }
+            key.verify_strict(&hash, &signature).map_err(|_| String::from("the signature doesn't match its key"))?;
+
+            let key = hex::encode(key.as_bytes());
+            Ok(match self.trusted_name(&key) {
+                Some(name) => TrustStatus::Verified(String::from(name)),
+                None => TrustStatus::UntrustedKey(key),
+            })
+        };
+        verify().unwrap_or_else(TrustStatus::Invalid)
+    }
+
+    /// Whether a brick with `status` may be loaded, warns about the ones that are let through under the warn policy
+    pub fn allows(&self, status: &TrustStatus, source: &str) -> bool {
+        match (status, self.policy) {
+            (TrustStatus::Verified(_), _) => true,
+            (TrustStatus::Invalid(_), _) | (_, UnsignedBricks::Block) => false,
+            (_, UnsignedBricks::Warn) => {
+                main_log_warning!("Loading {source}, which is {status}");
+                true
+            }
+            (_, UnsignedBricks::Allow) => true,
+        }
+    }
+
+    /// Adds `key` to the trusted keys file, bricks it signed are verified from the next start
+    pub fn trust(&mut self, name: &str, key: &str) -> Result<(), String> {
+        if self.trusted_name(key).is_some() {
+            return Ok(());
+        }
+
+        let mut keys = self.keys.clone();
+        keys.push(TrustedKey { name: String::from(name), key: String::from(key) });
+        let text = toml::to_string(&TrustedKeys { keys }).map_err(|e| e.to_string())?;
+        fs::write(&self.keys_path, text).map_err(|e| format!("could not write {}: {e}", self.keys_path.display()))?;
+
+        self.keys.push(TrustedKey { name: String::from(name), key: String::from(key) });
+        Ok(())
+    }
+
+    /// Shows `status`, with a button to trust its key if it is signed by an unknown one.
+    /// The player names the key themselves, since a brick can claim to be by anyone.
+    pub fn draw_status(&mut self, ui: &Ui, status: &TrustStatus) {
+        ui.text_colored(status.color(), status.to_string());
+        let TrustStatus::UntrustedKey(key) = status else {
+            return;
+        };
+        if ui.is_item_hovered() {
+            ui.tooltip_text(format!("Key {key}"));
+        }
+
+        let _id = ui.push_id(key);
+        ui.same_line();
+        if self.trusted_name(key).is_some() {
+            ui.text_disabled("trusted from the next start");
+            return;
+        }
+
+        let mut trusted_as = None;
+        match &mut self.naming {
+            Some((naming, name)) if naming == key => {
+                ui.set_next_item_width(150.0);
+                ui.input_text("##KeyName", name).hint("Who owns this key").build();
+                ui.same_line();
+                ui.disabled(name.trim().is_empty(), || {
+                    if ui.small_button("Trust") {
+                        trusted_as = Some(name.trim().to_string());
+                    }
+                });
+                ui.same_line();
+                if ui.small_button("Cancel") {
+                    self.naming = None;
+                }
+            }
+            _ => {
+                if ui.small_button("Trust key") {
+                    self.naming = Some((key.clone(), String::new()));
+                }
+            }
+        }
+
+        if let Some(name) = trusted_as {
+            match self.trust(&name, key) {
+                Ok(()) => self.naming = None,
+                Err(e) => main_log_error!("Could not trust {name}'s key: {e}"),
+            }
+        }
+    }
+}
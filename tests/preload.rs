@@ -0,0 +1,102 @@
+//! Preloads the loader into a program that knows nothing about BlueBrick and checks it loaded a brick
+
+#![cfg(target_os = "linux")]
+
+use std::{env, fs, path::{Path, PathBuf}, process::{Command, Stdio}, thread, time::{Duration, Instant}};
+
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+/// The smallest brick the loader accepts, written in C so the test doesn't need a second crate
+const TEST_BRICK: &str = r#"
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+const char *name(void) { return "PreloadTestBrick"; }
+const char *author(void) { return "BlueBrick tests"; }
+const char *version(void) { return "1.0.0"; }
+
+bool set_host_api(const void *api) { return api != NULL; }
+void *new(void) { static int brick; return &brick; }
+void init(void *brick) {}
+bool enable(void *brick) { return true; }
+bool disable(void *brick) { return true; }
+
+void set_imgui_ctx(void *ctx) {}
+void draw(void *brick, const void *ui) {}
+
+void build_menu(void *brick, void *menu) {}
+void menu_clicked(void *brick, uint32_t id, bool checked) {}
+void draw_window(void *brick, uint32_t id, const void *ui, bool *opened) {}
+"#;
+
+/// Cargo builds the loader next to the test's own folder
+fn loader_path() -> PathBuf {
+    let deps = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    [deps.join("libbluebrick.so"), deps.parent().unwrap().join("libbluebrick.so")]
+        .into_iter()
+        .find(|path| path.exists())
+        .expect("libbluebrick.so was not built")
+}
+
+fn build_brick(out: &Path) {
+    let source = out.with_extension("c");
+    fs::write(&source, TEST_BRICK).unwrap();
+
+    let compiler = env::var("CC").unwrap_or_else(|_| String::from("cc"));
+    let status = Command::new(&compiler)
+        .args(["-shared", "-fPIC", "-o"])
+        .arg(out)
+        .arg(&source)
+        .status()
+        .unwrap_or_else(|e| panic!("could not run {compiler} to build the test brick: {e}"));
+    assert!(status.success(), "{compiler} failed to build the test brick");
+}
+
+fn wait_for_log(log: &Path, text: &str) -> Option<String> {
+    let started = Instant::now();
+    while started.elapsed() < TIMEOUT {
+        if let Ok(contents) = fs::read_to_string(log)
+            && contents.contains(text)
+        {
+            return Some(contents);
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    fs::read_to_string(log).ok()
+}
+
+#[test]
+fn preloaded_loader_loads_bricks() {
+    let root = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("preload-root");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("mods")).unwrap();
+    fs::create_dir_all(root.join("libraries")).unwrap();
+    build_brick(&root.join("mods").join("libpreload_test_brick.so"));
+
+    // the dummy program writes down what its children would be preloaded with, then waits on its stdin while the loader starts
+    let child_preload = root.join("child-preload");
+    let mut host = Command::new("sh")
+        .args(["-c", r#"printf %s "$LD_PRELOAD" > "$1"; read -r line"#, "sh"])
+        .arg(&child_preload)
+        .stdin(Stdio::piped())
+        .env("LD_PRELOAD", loader_path())
+        .env("BLUEBRICK_ROOT", &root)
+        .env("BLUEBRICK_LOG_SINKS", "file")
+        .env("BLUEBRICK_LOG_LEVEL", "debug")
+        .env("BLUEBRICK_OVERLAY", "off")
+        .stdout(Stdio::null())
+        .spawn()
+        .expect("could not start the host program");
+
+    let log = wait_for_log(&root.join("logs").join("latest.log"), "Enabled PreloadTestBrick");
+    let _ = host.kill();
+    let _ = host.wait();
+
+    let log = log.expect("the loader never wrote a log, so it didn't start");
+    assert!(log.contains("Loaded PreloadTestBrick v1.0.0 by BlueBrick tests"), "the brick wasn't loaded:\n{log}");
+    assert!(log.contains("Enabled PreloadTestBrick"), "the brick wasn't enabled:\n{log}");
+
+    let child_preload = fs::read_to_string(&child_preload).unwrap();
+    assert!(!child_preload.contains("libbluebrick.so"), "children would start BlueBrick too: LD_PRELOAD={child_preload}");
+}
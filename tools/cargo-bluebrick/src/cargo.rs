@@ -0,0 +1,69 @@
+//! Runs `cargo build` on the brick and finds the files it built
+
+use std::{env, ffi::OsStr, io::{BufRead, BufReader}, path::{Path, PathBuf}, process::{Command, Stdio}};
+
+use serde::Deserialize;
+
+pub struct BuildOptions {
+    pub release: bool,
+    pub target: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Message {
+    reason: String,
+    manifest_path: Option<PathBuf>,
+    target: Option<Target>,
+    #[serde(default)]
+    filenames: Vec<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct Target {
+    kind: Vec<String>,
+}
+
+fn is_brick_file(path: &Path) -> bool {
+    ["dll", "so", "dylib"].map(OsStr::new).contains(&path.extension().unwrap_or_default())
+}
+
+/// Builds the crate and returns the dlls or shared objects it produced
+pub fn build(manifest_path: &Path, options: &BuildOptions) -> Result<Vec<PathBuf>, String> {
+    // cargo sets this when it runs a subcommand, so the same toolchain builds the brick
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+
+    let mut command = Command::new(cargo);
+    command.arg("build").arg("--manifest-path").arg(manifest_path).arg("--message-format=json-render-diagnostics");
+    if options.release {
+        command.arg("--release");
+    }
+    if let Some(target) = &options.target {
+        command.args(["--target", target]);
+    }
+
+    let mut child = command.stdout(Stdio::piped()).spawn().map_err(|e| format!("could not run cargo: {e}"))?;
+    let stdout = child.stdout.take().expect("stdout is piped");
+
+    let mut built = Vec::new();
+    for line in BufReader::new(stdout).lines() {
+        let line = line.map_err(|e| format!("could not read cargo's output: {e}"))?;
+        let Ok(message) = serde_json::from_str::<Message>(&line) else {
+            continue;
+        };
+
+        let is_cdylib = message.target.is_some_and(|target| target.kind.iter().any(|kind| kind == "cdylib"));
+        if message.reason == "compiler-artifact" && is_cdylib && message.manifest_path.as_deref() == Some(manifest_path) {
+            built.extend(message.filenames.into_iter().filter(|file| is_brick_file(file)));
+        }
+    }
+
+    let status = child.wait().map_err(|e| format!("could not wait for cargo: {e}"))?;
+    if !status.success() {
+        return Err(String::from("the brick failed to build"));
+    }
+    if built.is_empty() {
+        return Err(String::from("the brick built no dll or shared object, is its crate-type \"cdylib\"?"));
+    }
+
+    Ok(built)
+}
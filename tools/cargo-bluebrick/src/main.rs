@@ -0,0 +1,283 @@
+mod cargo;
+mod manifest;
+mod package;
+mod sign;
+mod template;
+
+use std::{env, fs, path::{Path, PathBuf}, process::ExitCode};
+
+use cargo::BuildOptions;
+use manifest::BrickKind;
+use template::BrickOptions;
+
+const DEFAULT_KEY: &str = "bluebrick-signing.key";
+const DEFAULT_BINDINGS: &str = "bluebrick = { git = \"https://github.com/XdotCore/BlueBrick\" }";
+
+const USAGE: &str = "\
+Scaffolds, builds and installs BlueBrick bricks
+
+Usage: cargo bluebrick COMMAND [OPTIONS]
+
+Commands:
+  new PATH       Makes a brick crate in PATH
+  build          Builds the brick and checks it exports everything the loader needs
+  install        Builds the brick and copies it into the game's bluebrick folder
+  package        Builds the brick and packs it with its README.md, LICENSE and assets folder into a .brick
+  check [FILE]   Checks the exports of an already built brick, or builds the brick first
+  keygen         Makes a signing key, players who trust its public key can load what it signs
+  sign FILE      Signs an already built brick
+
+Options for new:
+      --mod               Makes a mod, the default
+      --library           Makes a library, which is loaded before the mods
+  -n, --name NAME         The brick's name in the overlay and logs, the folder's name by default
+  -a, --author AUTHOR     The brick's author, the current user by default
+      --bindings PATH     Use a local rust-bindings folder instead of the git repository
+
+Options for build, install, package and check:
+      --manifest-path PATH  The brick's Cargo.toml, ./Cargo.toml by default
+  -r, --release             Build with the release profile
+      --target TRIPLE       Build for another target, like i686-pc-windows-msvc for 32 bit games
+      --game-dir DIR        The game's folder to install into, overrides BLUEBRICK_GAME_DIR and
+                            game-dir under [package.metadata.bluebrick]
+      --key FILE            Signs what is built with this key, for sign and keygen it is
+                            bluebrick-signing.key by default
+  -h, --help                Show this message";
+
+enum Subcommand {
+    New { path: PathBuf },
+    Build,
+    Install,
+    Package,
+    Check { file: Option<PathBuf> },
+    Keygen,
+    Sign { file: PathBuf },
+}
+
+struct Options {
+    command: Subcommand,
+    kind: BrickKind,
+    name: Option<String>,
+    author: Option<String>,
+    bindings: Option<PathBuf>,
+    manifest_path: PathBuf,
+    build: BuildOptions,
+    game_dir: Option<PathBuf>,
+    key: Option<PathBuf>,
+}
+
+fn parse_args() -> Result<Option<Options>, String> {
+    let mut args = env::args().skip(1).peekable();
+    // cargo passes the subcommand's name first when run as `cargo bluebrick`
+    if args.peek().map(String::as_str) == Some("bluebrick") {
+        args.next();
+    }
+
+    let mut command = None;
+    let mut path: Option<PathBuf> = None;
+    let mut options = Options {
+        command: Subcommand::Build,
+        kind: BrickKind::Mod,
+        name: None,
+        author: None,
+        bindings: None,
+        manifest_path: PathBuf::from("Cargo.toml"),
+        build: BuildOptions { release: false, target: None },
+        game_dir: None,
+        key: None,
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{name} needs a value"));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--mod" => options.kind = BrickKind::Mod,
+            "--library" => options.kind = BrickKind::Library,
+            "-n" | "--name" => options.name = Some(value(&arg)?),
+            "-a" | "--author" => options.author = Some(value(&arg)?),
+            "--bindings" => options.bindings = Some(PathBuf::from(value(&arg)?)),
+            "--manifest-path" => options.manifest_path = PathBuf::from(value(&arg)?),
+            "-r" | "--release" => options.build.release = true,
+            "--target" => options.build.target = Some(value(&arg)?),
+            "--game-dir" => options.game_dir = Some(PathBuf::from(value(&arg)?)),
+            "--key" => options.key = Some(PathBuf::from(value(&arg)?)),
+            arg if arg.starts_with('-') => return Err(format!("unknown option {arg}")),
+            arg if command.is_none() => command = Some(String::from(arg)),
+            arg if path.is_none() => path = Some(PathBuf::from(arg)),
+            arg => return Err(format!("unexpected argument {arg}")),
+        }
+    }
+
+    options.command = match (command.as_deref(), path) {
+        (Some("new"), Some(path)) => Subcommand::New { path },
+        (Some("new"), None) => return Err(String::from("new needs a path")),
+        (Some("check"), file) => Subcommand::Check { file },
+        (Some("sign"), Some(file)) => Subcommand::Sign { file },
+        (Some("sign"), None) => return Err(String::from("sign needs a file")),
+        (Some("build" | "install" | "package" | "keygen"), Some(path)) => return Err(format!("unexpected argument {}", path.display())),
+        (Some("build"), None) => Subcommand::Build,
+        (Some("install"), None) => Subcommand::Install,
+        (Some("package"), None) => Subcommand::Package,
+        (Some("keygen"), None) => Subcommand::Keygen,
+        (Some(command), _) => return Err(format!("unknown command {command}")),
+        (None, _) => return Ok(None),
+    };
+
+    Ok(Some(options))
+}
+
+fn new(path: &Path, options: &Options) -> Result<(), String> {
+    let folder_name = path.file_name().map(|name| name.to_string_lossy().to_string()).ok_or_else(|| format!("{} has no folder name", path.display()))?;
+    let crate_name = folder_name.chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect::<String>();
+
+    let bindings = match &options.bindings {
+        Some(bindings) => {
+            let bindings = bindings.canonicalize().map_err(|e| format!("could not find the bindings at {}: {e}", bindings.display()))?;
+            format!("bluebrick = {{ path = {:?} }}", bindings.to_string_lossy())
+        }
+        None => String::from(DEFAULT_BINDINGS),
+    };
+    let author = options.author.clone().or_else(|| env::var("USER").or_else(|_| env::var("USERNAME")).ok()).unwrap_or_else(|| String::from("Unknown"));
+
+    let brick = BrickOptions {
+        name: options.name.clone().unwrap_or(folder_name),
+        crate_name,
+        author,
+        kind: options.kind,
+        bindings,
+    };
+    template::write_crate(path, &brick).map_err(|e| format!("could not write the crate: {e}"))?;
+
+    println!("Made the {} {} in {}", brick.kind.name(), brick.name, path.display());
+    println!("Build it with `cargo bluebrick build` and install it with `cargo bluebrick install --game-dir GAME`");
+    Ok(())
+}
+
+/// Checks a built brick exports all of `SubBrickApi`, so a brick the loader would skip never gets installed
+fn check_exports(file: &Path) -> Result<(), String> {
+    let bytes = fs::read(file).map_err(|e| format!("could not read {}: {e}", file.display()))?;
+    let exports = match bluebrick_exports::read_exports(&bytes).map_err(|e| format!("could not read the exports of {}: {e}", file.display()))? {
+        Some(exports) => exports,
+        None => {
+            println!("Skipped checking {}, only dlls and shared objects can be checked", file.display());
+            return Ok(());
+        }
+    };
+
+    let missing = exports.missing();
+    if !missing.is_empty() {
+        return Err(format!(
+            "{} is missing {} of the {} functions BlueBrick loads: {}\nIs the brick's struct marked with #[bluebrick_mod] or #[bluebrick_library]?",
+            file.display(),
+            missing.len(),
+            bluebrick_exports::SUBBRICK_API.len(),
+            missing.join(", "),
+        ));
+    }
+
+    println!("Checked {} ({}), it exports every function BlueBrick loads", file.display(), exports.kind);
+    Ok(())
+}
+
+fn build(options: &Options) -> Result<(manifest::Brick, Vec<PathBuf>), String> {
+    let brick = manifest::read(&options.manifest_path)?;
+    let built = cargo::build(&brick.manifest_path, &options.build)?;
+    for file in &built {
+        check_exports(file)?;
+        if let Some(key) = &options.key {
+            sign(file, key)?;
+        }
+    }
+    Ok((brick, built))
+}
+
+fn keygen(options: &Options) -> Result<(), String> {
+    let path = options.key.as_deref().unwrap_or(Path::new(DEFAULT_KEY));
+    let public = sign::keygen(path)?;
+
+    println!("Made a signing key in {}, keep it private and out of version control", path.display());
+    println!("Its public key is {public}");
+    println!("Players trust it by adding it to trusted_keys.toml in their bluebrick folder:\n\n[[key]]\nname = \"Your name\"\nkey = \"{public}\"");
+    Ok(())
+}
+
+fn sign(file: &Path, key: &Path) -> Result<(), String> {
+    let out = sign::sign(file, key)?;
+    println!("Signed {} into {}", file.display(), out.display());
+    Ok(())
+}
+
+fn install(options: &Options) -> Result<(), String> {
+    let (brick, built) = build(options)?;
+
+    let game_dir = options.game_dir.clone().or_else(|| env::var_os("BLUEBRICK_GAME_DIR").filter(|dir| !dir.is_empty()).map(PathBuf::from)).or(brick.game_dir);
+    let folder = manifest::brick_dir(game_dir.as_deref())?.join(brick.kind.folder());
+    fs::create_dir_all(&folder).map_err(|e| format!("could not make {}: {e}", folder.display()))?;
+
+    for file in built {
+        let to = folder.join(file.file_name().unwrap_or_default());
+        // the game keeps bricks open while it runs, which is the usual reason this fails on windows
+        fs::copy(&file, &to).map_err(|e| format!("could not copy {} to {}, is the game running? {e}", file.display(), to.display()))?;
+
+        // an old signature left next to a new binary would get it blocked as tampered
+        let signature = sign::signature_path(&file);
+        let signature_to = sign::signature_path(&to);
+        let copied = if options.key.is_some() { fs::copy(&signature, &signature_to).map(|_| ()) } else { fs::remove_file(&signature_to) };
+        if let Err(e) = copied
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            return Err(format!("could not update {}: {e}", signature_to.display()));
+        }
+        println!("Installed {} {} to {}", brick.kind.name(), brick.package, to.display());
+    }
+    Ok(())
+}
+
+fn package(options: &Options) -> Result<(), String> {
+    // checked before building, so a bad target doesn't waste a build
+    let platform = package::platform(options.build.target.as_deref())?;
+    let (brick, built) = build(options)?;
+
+    for binary in built {
+        let signature = options.key.is_some().then(|| sign::signature_path(&binary));
+        let out = package::write_package(&brick, &binary, signature.as_deref(), &platform)?;
+        println!("Packed {} {} for {platform} into {}", brick.kind.name(), brick.name, out.display());
+    }
+    println!("Players install it by putting it in the install folder next to their libraries and mods folders");
+    Ok(())
+}
+
+fn run(options: Options) -> Result<(), String> {
+    match &options.command {
+        Subcommand::New { path } => new(path, &options),
+        Subcommand::Build => build(&options).map(|_| ()),
+        Subcommand::Install => install(&options),
+        Subcommand::Package => package(&options),
+        Subcommand::Check { file: Some(file) } => check_exports(file),
+        Subcommand::Check { file: None } => build(&options).map(|_| ()),
+        Subcommand::Keygen => keygen(&options),
+        Subcommand::Sign { file } => sign(file, options.key.as_deref().unwrap_or(Path::new(DEFAULT_KEY))),
+    }
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args() {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
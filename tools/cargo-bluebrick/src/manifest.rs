@@ -0,0 +1,137 @@
+//! The brick's settings in its Cargo.toml and where the game wants it installed
+
+use std::{env, fs, io, path::{Path, PathBuf}};
+
+use serde::Deserialize;
+
+#[derive(Clone, Copy, PartialEq, Deserialize, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum BrickKind {
+    Library,
+    Mod,
+}
+
+impl BrickKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            BrickKind::Library => "library",
+            BrickKind::Mod => "mod",
+        }
+    }
+
+    /// The folder under the brick dir the loader reads this kind from
+    pub fn folder(self) -> &'static str {
+        match self {
+            BrickKind::Library => "libraries",
+            BrickKind::Mod => "mods",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    package: Option<Package>,
+}
+
+/// Fields that can be inherited from a workspace are read as values, and only used when they are set directly
+#[derive(Deserialize)]
+struct Package {
+    name: String,
+    version: Option<toml::Value>,
+    description: Option<toml::Value>,
+    authors: Option<toml::Value>,
+    #[serde(default)]
+    metadata: Metadata,
+}
+
+#[derive(Deserialize, Default)]
+struct Metadata {
+    #[serde(default)]
+    bluebrick: BrickMetadata,
+}
+
+/// `[package.metadata.bluebrick]`
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+struct BrickMetadata {
+    name: Option<String>,
+    kind: Option<BrickKind>,
+    game_dir: Option<PathBuf>,
+}
+
+pub struct Brick {
+    pub manifest_path: PathBuf,
+    pub package: String,
+    /// The name packages show, the package's name unless the metadata sets one
+    pub name: String,
+    pub version: Option<String>,
+    pub description: String,
+    pub authors: Vec<String>,
+    pub kind: BrickKind,
+    /// Relative folders are made absolute against the crate's folder
+    pub game_dir: Option<PathBuf>,
+}
+
+/// Reads the brick's Cargo.toml, bricks without a `kind` are mods
+pub fn read(manifest_path: &Path) -> Result<Brick, String> {
+    let manifest_path = manifest_path.canonicalize().map_err(|e| format!("could not find {}: {e}", manifest_path.display()))?;
+    let text = fs::read_to_string(&manifest_path).map_err(|e| format!("could not read {}: {e}", manifest_path.display()))?;
+    let manifest: Manifest = toml::from_str(&text).map_err(|e| format!("could not parse {}: {}", manifest_path.display(), e.message()))?;
+    let package = manifest.package.ok_or_else(|| format!("{} is a workspace, point --manifest-path at the brick's Cargo.toml", manifest_path.display()))?;
+
+    let crate_dir = manifest_path.parent().unwrap_or(Path::new("."));
+    let BrickMetadata { name, kind, game_dir } = package.metadata.bluebrick;
+    let string = |value: Option<toml::Value>| value.as_ref().and_then(toml::Value::as_str).map(String::from);
+    let authors = match package.authors {
+        Some(toml::Value::Array(authors)) => authors.iter().filter_map(toml::Value::as_str).map(String::from).collect(),
+        _ => Vec::new(),
+    };
+
+    Ok(Brick {
+        name: name.unwrap_or_else(|| package.name.clone()),
+        version: string(package.version),
+        description: string(package.description).unwrap_or_default(),
+        authors,
+        package: package.name,
+        kind: kind.unwrap_or(BrickKind::Mod),
+        game_dir: game_dir.map(|dir| crate_dir.join(dir)),
+        manifest_path,
+    })
+}
+
+/// Only the setting of the game's `config.toml` that moves the bricks
+#[derive(Deserialize, Default)]
+struct GameConfig {
+    brick_dir: Option<String>,
+}
+
+/// The folder the loader reads `libraries` and `mods` from, found the same way the loader does
+pub fn brick_dir(game_dir: Option<&Path>) -> Result<PathBuf, String> {
+    let var = |key: &str| env::var_os(key).filter(|value| !value.is_empty()).map(PathBuf::from);
+
+    let root = match (var("BLUEBRICK_ROOT"), game_dir) {
+        (Some(root), _) => root,
+        (None, Some(game_dir)) => game_dir.join("bluebrick"),
+        (None, None) => {
+            return Err(String::from(
+                "no game folder is set, pass --game-dir, set BLUEBRICK_GAME_DIR or set game-dir under [package.metadata.bluebrick]",
+            ));
+        }
+    };
+
+    if let Some(brick_dir) = var("BLUEBRICK_BRICK_DIR") {
+        return Ok(root.join(brick_dir));
+    }
+
+    let config_path = root.join("config.toml");
+    let config = match fs::read_to_string(&config_path) {
+        Ok(text) => toml::from_str::<GameConfig>(&text).map_err(|e| format!("could not parse {}: {}", config_path.display(), e.message()))?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => GameConfig::default(),
+        Err(e) => return Err(format!("could not read {}: {e}", config_path.display())),
+    };
+
+    Ok(match config.brick_dir {
+        Some(brick_dir) => root.join(brick_dir),
+        None => root,
+    })
+}
@@ -0,0 +1,118 @@
+//! Writes `.brick` packages, the archive format the loader installs from its `install` folder.
+//! The format is described in the loader's `packages.rs`.
+
+use std::{env, fs::{self, File}, io::{self, Write}, path::{Path, PathBuf}};
+
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+use crate::manifest::Brick;
+
+const LICENSE_NAMES: &[&str] = &["LICENSE", "LICENSE.md", "LICENSE.txt", "LICENCE", "LICENCE.md", "LICENCE.txt"];
+
+/// The loader's name for a platform, `env::consts::OS` and `env::consts::ARCH` joined by a dash
+pub fn platform(target: Option<&str>) -> Result<String, String> {
+    let Some(target) = target else {
+        return Ok(format!("{}-{}", env::consts::OS, env::consts::ARCH));
+    };
+
+    let arch = match target.split('-').next().unwrap_or_default() {
+        "i386" | "i586" | "i686" => "x86",
+        "x86_64" => "x86_64",
+        "aarch64" => "aarch64",
+        arch => return Err(format!("packages can't be made for the {arch} architecture yet")),
+    };
+    let os = if target.contains("-windows") {
+        "windows"
+    } else if target.contains("-linux") {
+        "linux"
+    } else if target.contains("-darwin") {
+        "macos"
+    } else {
+        return Err(format!("packages can't be made for {target} yet"));
+    };
+    Ok(format!("{os}-{arch}"))
+}
+
+fn manifest(brick: &Brick, version: &str, platform: &str, binary: &str, readme: Option<&str>, license: Option<&str>) -> String {
+    let mut manifest = format!(
+        "\
+id = {id:?}
+name = {name:?}
+version = {version:?}
+kind = {kind:?}
+author = {author:?}
+description = {description:?}
+",
+        id = brick.package,
+        name = brick.name,
+        kind = brick.kind.name(),
+        author = brick.authors.join(", "),
+        description = brick.description,
+    );
+    if let Some(readme) = readme {
+        manifest += &format!("readme = {readme:?}\n");
+    }
+    if let Some(license) = license {
+        manifest += &format!("license = {license:?}\n");
+    }
+    manifest += &format!("\n[binaries]\n{platform:?} = {binary:?}\n");
+    manifest
+}
+
+/// Every file under `folder`, as paths relative to `root` with forward slashes like zip expects
+fn files_under(root: &Path, folder: &Path, files: &mut Vec<(String, PathBuf)>) -> io::Result<()> {
+    let mut entries = fs::read_dir(folder)?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            files_under(root, &path, files)?;
+        } else {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            let name = relative.components().map(|component| component.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+            files.push((name, path));
+        }
+    }
+    Ok(())
+}
+
+/// Packs the built binary and its signature with the crate's readme, licence and `assets` folder, returns where the package was written
+pub fn write_package(brick: &Brick, binary: &Path, signature: Option<&Path>, platform: &str) -> Result<PathBuf, String> {
+    let version = brick.version.as_deref().ok_or("the package's version has to be set in its Cargo.toml to make a package")?;
+    let crate_dir = brick.manifest_path.parent().unwrap_or(Path::new("."));
+
+    let binary_name = binary.file_name().unwrap_or_default().to_string_lossy();
+    let binary_path = format!("bin/{platform}/{binary_name}");
+
+    let mut files = vec![(binary_path.clone(), binary.to_path_buf())];
+    if let Some(signature) = signature {
+        files.push((format!("{binary_path}.sig"), signature.to_path_buf()));
+    }
+    let readme = crate_dir.join("README.md").exists().then_some("README.md");
+    let license = LICENSE_NAMES.iter().copied().find(|name| crate_dir.join(name).exists());
+    files.extend(readme.iter().chain(license.iter()).map(|name| (String::from(*name), crate_dir.join(name))));
+
+    let assets = crate_dir.join("assets");
+    if assets.is_dir() {
+        files_under(crate_dir, &assets, &mut files).map_err(|e| format!("could not read {}: {e}", assets.display()))?;
+    }
+
+    let out = binary.with_file_name(format!("{}-{version}-{platform}.brick", brick.package));
+    let write = || -> zip::result::ZipResult<()> {
+        let mut zip = ZipWriter::new(File::create(&out)?);
+        let options = SimpleFileOptions::default();
+
+        zip.start_file("brick.toml", options)?;
+        zip.write_all(manifest(brick, version, platform, &binary_path, readme, license).as_bytes())?;
+        for (name, path) in &files {
+            zip.start_file(name.as_str(), options)?;
+            io::copy(&mut File::open(path)?, &mut zip)?;
+        }
+        zip.finish()?;
+        Ok(())
+    };
+    write().map_err(|e| format!("could not write {}: {e}", out.display()))?;
+
+    Ok(out)
+}
@@ -0,0 +1,69 @@
+//! Signing keys and the `.sig` files the loader checks bricks against.
+//! The format is described in the loader's `trust.rs`.
+
+use std::{fs::{self, OpenOptions}, io::Write, path::{Path, PathBuf}};
+
+use ed25519_dalek::{Signer, SigningKey};
+use sha2::{Digest, Sha256};
+
+/// Makes a new signing key and writes it to `path`, returns its public key as hex
+pub fn keygen(path: &Path) -> Result<String, String> {
+    let mut secret = [0u8; 32];
+    getrandom::getrandom(&mut secret).map_err(|e| format!("could not make a key: {e}"))?;
+    let key = SigningKey::from_bytes(&secret);
+
+    // never overwrites, a lost key means every player has to trust a new one
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    // only readable by its owner
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options
+        .open(path)
+        .map_err(|e| format!("could not make {}: {e}", path.display()))?;
+    let text = format!("# BlueBrick signing key, keep it private\nsecret = \"{}\"\n", hex::encode(secret));
+    file.write_all(text.as_bytes()).map_err(|e| format!("could not write {}: {e}", path.display()))?;
+
+    Ok(hex::encode(key.verifying_key().as_bytes()))
+}
+
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeyFile {
+    secret: String,
+}
+
+fn read_key(path: &Path) -> Result<SigningKey, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("could not read the key {}: {e}", path.display()))?;
+    let file: KeyFile = toml::from_str(&text).map_err(|e| format!("could not parse the key {}: {}", path.display(), e.message()))?;
+    let secret = hex::decode(file.secret.trim())
+        .ok()
+        .and_then(|secret| <[u8; 32]>::try_from(secret).ok())
+        .ok_or_else(|| format!("{} doesn't hold a key made by `cargo bluebrick keygen`", path.display()))?;
+    Ok(SigningKey::from_bytes(&secret))
+}
+
+/// Where the loader looks for a binary's signature
+pub fn signature_path(binary: &Path) -> PathBuf {
+    let mut path = binary.as_os_str().to_os_string();
+    path.push(".sig");
+    PathBuf::from(path)
+}
+
+/// Signs `binary` with the key in `key_path` and writes the signature next to it, returns where it was written
+pub fn sign(binary: &Path, key_path: &Path) -> Result<PathBuf, String> {
+    let key = read_key(key_path)?;
+    let bytes = fs::read(binary).map_err(|e| format!("could not read {}: {e}", binary.display()))?;
+    let digest = Sha256::digest(&bytes);
+    let signature = key.sign(&digest);
+
+    let text = format!(
+        "sha256 = \"{}\"\nkey = \"{}\"\nsignature = \"{}\"\n",
+        hex::encode(digest),
+        hex::encode(key.verifying_key().as_bytes()),
+        hex::encode(signature.to_bytes()),
+    );
+    let out = signature_path(binary);
+    fs::write(&out, text).map_err(|e| format!("could not write {}: {e}", out.display()))?;
+    Ok(out)
+}
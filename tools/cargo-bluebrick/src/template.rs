@@ -0,0 +1,127 @@
+//! Writes a new brick crate that builds as is
+
+use std::{fs, io, path::Path};
+
+use crate::manifest::BrickKind;
+
+pub struct BrickOptions {
+    /// The crate name, which is also the built file's name
+    pub crate_name: String,
+    /// The name shown in the overlay and the logs
+    pub name: String,
+    pub author: String,
+    pub kind: BrickKind,
+    /// The `bluebrick` dependency line
+    pub bindings: String,
+}
+
+/// The brick's struct name, `my-cool_mod` becomes `MyCoolMod`
+fn type_name(crate_name: &str) -> String {
+    let name = crate_name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map(|first| first.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+        })
+        .collect::<String>();
+
+    match name.chars().next() {
+        Some(first) if first.is_ascii_alphabetic() => name,
+        _ => format!("Brick{name}"),
+    }
+}
+
+fn cargo_toml(options: &BrickOptions) -> String {
+    format!(
+        "\
+[package]
+name = \"{crate_name}\"
+version = \"0.1.0\"
+edition = \"2024\"
+authors = [{author:?}]
+
+[dependencies]
+{bindings}
+
+[lib]
+crate-type = [\"cdylib\"]
+
+# read by cargo bluebrick
+[package.metadata.bluebrick]
+name = {name:?}
+kind = \"{kind}\"
+# the game's folder, install copies the brick into its bluebrick folder
+# game-dir = \"C:/Program Files (x86)/Steam/steamapps/common/Game\"
+
+# keeps the brick out of any workspace it was made in
+[workspace]
+",
+        crate_name = options.crate_name,
+        name = options.name,
+        author = options.author,
+        bindings = options.bindings,
+        kind = options.kind.name(),
+    )
+}
+
+fn lib_rs(options: &BrickOptions) -> String {
+    let (attribute, kind_trait) = match options.kind {
+        BrickKind::Library => ("bluebrick_library", "Library"),
+        BrickKind::Mod => ("bluebrick_mod", "Mod"),
+    };
+
+    format!(
+        "\
+use bluebrick::imgui::Ui;
+use bluebrick::logger::Logger;
+use bluebrick::subbrick::{{{kind_trait}, SubBrick}};
+use bluebrick::{{{attribute}, log}};
+
+#[{attribute}({name:?}, {author:?})]
+pub struct {type_name} {{
+}}
+
+impl SubBrick for {type_name} {{
+    fn new() -> Self {{
+        Self {{}}
+    }}
+
+    fn init(&mut self) {{
+        log!(Self::logger(), \"Version {{}} is ready\", env!(\"CARGO_PKG_VERSION\"));
+    }}
+
+    fn enable(&mut self) -> bool {{
+        true
+    }}
+
+    fn disable(&mut self) -> bool {{
+        true
+    }}
+
+    fn draw(&mut self, _ui: &Ui) {{
+    }}
+}}
+
+impl {kind_trait} for {type_name} {{
+}}
+",
+        name = options.name,
+        author = options.author,
+        type_name = type_name(&options.crate_name),
+    )
+}
+
+/// Writes the crate to `out`, which must not exist yet
+pub fn write_crate(out: &Path, options: &BrickOptions) -> io::Result<()> {
+    if out.exists() {
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} already exists", out.display())));
+    }
+
+    fs::create_dir_all(out.join("src"))?;
+    fs::write(out.join("Cargo.toml"), cargo_toml(options))?;
+    fs::write(out.join("src").join("lib.rs"), lib_rs(options))?;
+    fs::write(out.join(".gitignore"), "target/\nCargo.lock\n")?;
+
+    Ok(())
+}
@@ -0,0 +1,153 @@
+//! Just enough of the ELF format to read the symbols a shared object exports
+
+use std::collections::HashSet;
+
+pub const ELF_MAGIC: &[u8] = b"\x7fELF";
+
+const SHT_DYNSYM: u32 = 11;
+const SHN_UNDEF: u16 = 0;
+const STB_GLOBAL: u8 = 1;
+const STB_WEAK: u8 = 2;
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    is_64: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn slice(&self, offset: usize, len: usize) -> Result<&'a [u8], String> {
+        offset
+            .checked_add(len)
+            .and_then(|end| self.bytes.get(offset..end))
+            .ok_or_else(|| format!("the file ends before offset {:#x}", offset.saturating_add(len)))
+    }
+
+    /// The offset of entry `index` in a table of `size` byte entries, checked to fit in the file
+    /// so the entry's fields can be read at small offsets from it
+    fn entry(&self, table: usize, index: usize, size: usize) -> Result<usize, String> {
+        let entry = index
+            .checked_mul(size)
+            .and_then(|offset| table.checked_add(offset))
+            .ok_or_else(|| format!("entry {index} of the table at {table:#x} is out of range"))?;
+        self.slice(entry, size)?;
+        Ok(entry)
+    }
+
+    fn u8(&self, offset: usize) -> Result<u8, String> {
+        Ok(self.slice(offset, 1)?[0])
+    }
+
+    fn u16(&self, offset: usize) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.slice(offset, 2)?.try_into().unwrap()))
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.slice(offset, 4)?.try_into().unwrap()))
+    }
+
+    /// A field that is 4 bytes in 32 bit files and 8 in 64 bit ones
+    fn word(&self, offset: usize) -> Result<usize, String> {
+        match self.is_64 {
+            true => Ok(u64::from_le_bytes(self.slice(offset, 8)?.try_into().unwrap()) as usize),
+            false => Ok(self.u32(offset)? as usize),
+        }
+    }
+
+    fn c_string(&self, offset: usize) -> Result<String, String> {
+        let rest = self.bytes.get(offset..).ok_or_else(|| format!("string at {offset:#x} is past the end of the file"))?;
+        let len = rest.iter().position(|&byte| byte == 0).ok_or_else(|| format!("string at {offset:#x} is not terminated"))?;
+        Ok(String::from_utf8_lossy(&rest[..len]).to_string())
+    }
+}
+
+struct SectionHeader {
+    kind: u32,
+    offset: usize,
+    size: usize,
+    link: u32,
+    entry_size: usize,
+}
+
+fn elf_machine(machine: u16) -> String {
+    match machine {
+        3 => String::from("x86"),
+        62 => String::from("x64"),
+        183 => String::from("arm64"),
+        other => format!("unknown machine {other:#x}"),
+    }
+}
+
+pub struct SharedObject {
+    /// What the file was built for, like `x64`
+    pub machine: String,
+    pub symbols: HashSet<String>,
+}
+
+/// Reads the defined global symbols in `.dynsym`, which is what `dlsym` searches
+pub fn read_exports(bytes: &[u8]) -> Result<SharedObject, String> {
+    let mut reader = Reader { bytes, is_64: false };
+    reader.is_64 = match reader.u8(4)? {
+        1 => false,
+        2 => true,
+        class => return Err(format!("unknown ELF class {class}")),
+    };
+    if reader.u8(5)? != 1 {
+        return Err(String::from("only little endian ELF files are supported"));
+    }
+
+    let machine = elf_machine(reader.u16(0x12)?);
+    let (section_table, section_size, section_count) = match reader.is_64 {
+        true => (reader.word(0x28)?, reader.u16(0x3a)? as usize, reader.u16(0x3c)? as usize),
+        false => (reader.word(0x20)?, reader.u16(0x2e)? as usize, reader.u16(0x30)? as usize),
+    };
+
+    // the fields are read from fixed offsets, so entries must at least be as big as the standard ones
+    let (min_section_size, min_symbol_size) = if reader.is_64 { (64, 24) } else { (40, 16) };
+    if section_count > 0 && section_size < min_section_size {
+        return Err(format!("section headers of {section_size} bytes are too small"));
+    }
+
+    let mut sections = Vec::with_capacity(section_count);
+    for i in 0..section_count {
+        let section = reader.entry(section_table, i, section_size)?;
+        sections.push(match reader.is_64 {
+            true => SectionHeader {
+                kind: reader.u32(section + 4)?,
+                offset: reader.word(section + 24)?,
+                size: reader.word(section + 32)?,
+                link: reader.u32(section + 40)?,
+                entry_size: reader.word(section + 56)?,
+            },
+            false => SectionHeader {
+                kind: reader.u32(section + 4)?,
+                offset: reader.word(section + 16)?,
+                size: reader.word(section + 20)?,
+                link: reader.u32(section + 24)?,
+                entry_size: reader.word(section + 36)?,
+            },
+        });
+    }
+
+    let mut symbols = HashSet::new();
+    for dynsym in sections.iter().filter(|section| section.kind == SHT_DYNSYM) {
+        if dynsym.entry_size < min_symbol_size {
+            return Err(format!("symbols of {} bytes are too small", dynsym.entry_size));
+        }
+        let strings = sections.get(dynsym.link as usize).ok_or("the symbol table's string table is missing")?;
+
+        for i in 0..dynsym.size / dynsym.entry_size {
+            let symbol = reader.entry(dynsym.offset, i, dynsym.entry_size)?;
+            let (name, info, section) = match reader.is_64 {
+                true => (reader.u32(symbol)?, reader.u8(symbol + 4)?, reader.u16(symbol + 6)?),
+                false => (reader.u32(symbol)?, reader.u8(symbol + 12)?, reader.u16(symbol + 14)?),
+            };
+
+            if section != SHN_UNDEF && matches!(info >> 4, STB_GLOBAL | STB_WEAK) {
+                let name = strings.offset.checked_add(name as usize).ok_or_else(|| format!("symbol name {name:#x} is out of range"))?;
+                symbols.insert(reader.c_string(name)?);
+            }
+        }
+    }
+
+    Ok(SharedObject { machine, symbols })
+}
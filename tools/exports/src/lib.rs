@@ -0,0 +1,59 @@
+//! Reads the symbols dlls and shared objects export straight from their bytes, without loading them,
+//! so files built for another architecture or os can be read too
+
+pub mod elf;
+pub mod pe;
+
+use std::collections::HashSet;
+
+/// Every function the loader looks up in a brick, these must match `SubBrickApi` in the loader's `subbrick.rs`.
+/// The loader and `cargo bluebrick` both check bricks against it.
+pub const SUBBRICK_API: &[&str] = &[
+    "name",
+    "author",
+    "version",
+    "set_host_api",
+    "new",
+    "init",
+    "enable",
+    "disable",
+    "set_imgui_ctx",
+    "draw",
+    "build_menu",
+    "menu_clicked",
+    "draw_window",
+];
+
+const MACH_O_MAGICS: &[[u8; 4]] = &[[0xcf, 0xfa, 0xed, 0xfe], [0xce, 0xfa, 0xed, 0xfe], [0xca, 0xfe, 0xba, 0xbe]];
+
+pub struct Exports {
+    /// What the file was built for, like `x64 dll`
+    pub kind: String,
+    pub symbols: HashSet<String>,
+}
+
+impl Exports {
+    /// The parts of `SubBrickApi` the brick doesn't export
+    pub fn missing(&self) -> Vec<&'static str> {
+        SUBBRICK_API.iter().copied().filter(|symbol| !self.symbols.contains(*symbol)).collect()
+    }
+}
+
+/// Reads the exports of a dll or shared object, `None` if the format can't be checked yet
+pub fn read_exports(bytes: &[u8]) -> Result<Option<Exports>, String> {
+    if bytes.starts_with(b"MZ") {
+        let table = pe::read_exports(bytes)?;
+        return Ok(Some(Exports {
+            kind: format!("{} dll", table.machine),
+            symbols: table.exports.into_iter().filter_map(|export| export.name).collect(),
+        }));
+    }
+    if bytes.starts_with(elf::ELF_MAGIC) {
+        let object = elf::read_exports(bytes)?;
+        return Ok(Some(Exports { kind: format!("{} shared object", object.machine), symbols: object.symbols }));
+    }
+    if MACH_O_MAGICS.iter().any(|magic| bytes.starts_with(magic)) {
+        return Ok(None);
+    }
+    Err(String::from("not a dll or shared object"))
+}
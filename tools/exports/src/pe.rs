@@ -0,0 +1,188 @@
+//! Just enough of the PE format to read a dll's export table, without any Windows apis
+
+use std::fmt;
+
+const IMAGE_FILE_MACHINE_I386: u16 = 0x14c;
+const IMAGE_FILE_MACHINE_AMD64: u16 = 0x8664;
+const IMAGE_FILE_MACHINE_ARM64: u16 = 0xaa64;
+const PE32_MAGIC: u16 = 0x10b;
+const PE32_PLUS_MAGIC: u16 = 0x20b;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Machine {
+    X86,
+    X64,
+    Arm64,
+    Other(u16),
+}
+
+impl fmt::Display for Machine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Machine::X86 => write!(f, "x86"),
+            Machine::X64 => write!(f, "x64"),
+            Machine::Arm64 => write!(f, "arm64"),
+            Machine::Other(machine) => write!(f, "unknown machine {machine:#x}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Export {
+    pub ordinal: u32,
+    /// Exports can be by ordinal only
+    pub name: Option<String>,
+    /// Set when the dll forwards the export to another one, like `NTDLL.RtlAllocateHeap` or `OTHER.#12`
+    pub forward: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct ExportTable {
+    pub machine: Machine,
+    /// The name the dll was built with, which can differ from its file name
+    pub dll_name: String,
+    pub exports: Vec<Export>,
+}
+
+struct Section {
+    virtual_address: u32,
+    virtual_size: u32,
+    raw_pointer: u32,
+    raw_size: u32,
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    sections: Vec<Section>,
+}
+
+impl<'a> Reader<'a> {
+    fn slice(&self, offset: usize, len: usize) -> Result<&'a [u8], String> {
+        let end = offset.checked_add(len).ok_or_else(|| format!("offset {offset:#x} is out of range"))?;
+        self.bytes.get(offset..end).ok_or_else(|| format!("the file ends before offset {end:#x}"))
+    }
+
+    fn u16(&self, offset: usize) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.slice(offset, 2)?.try_into().unwrap()))
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.slice(offset, 4)?.try_into().unwrap()))
+    }
+
+    /// Turns an address in the loaded image into an offset in the file
+    fn rva_to_offset(&self, rva: u32) -> Result<usize, String> {
+        let section = self
+            .sections
+            .iter()
+            .find(|section| rva >= section.virtual_address && rva - section.virtual_address < section.virtual_size.max(section.raw_size))
+            .ok_or_else(|| format!("address {rva:#x} is not in any section"))?;
+        (rva - section.virtual_address)
+            .checked_add(section.raw_pointer)
+            .map(|offset| offset as usize)
+            .ok_or_else(|| format!("address {rva:#x} is out of range"))
+    }
+
+    /// The offset of entry `index` in a table of `size` byte entries
+    fn entry(table: usize, index: usize, size: usize) -> Result<usize, String> {
+        index
+            .checked_mul(size)
+            .and_then(|offset| table.checked_add(offset))
+            .ok_or_else(|| format!("entry {index} of the table at {table:#x} is out of range"))
+    }
+
+    fn c_string(&self, rva: u32) -> Result<String, String> {
+        let offset = self.rva_to_offset(rva)?;
+        let rest = self.bytes.get(offset..).ok_or_else(|| format!("string at {rva:#x} is past the end of the file"))?;
+        let len = rest.iter().position(|&byte| byte == 0).ok_or_else(|| format!("string at {rva:#x} is not terminated"))?;
+        Ok(String::from_utf8_lossy(&rest[..len]).to_string())
+    }
+}
+
+/// Reads the export table of a dll's bytes
+pub fn read_exports(bytes: &[u8]) -> Result<ExportTable, String> {
+    let mut reader = Reader { bytes, sections: Vec::new() };
+
+    if reader.slice(0, 2)? != b"MZ" {
+        return Err(String::from("not a dll, it has no MZ header"));
+    }
+    let pe = reader.u32(0x3c)? as usize;
+    if reader.slice(pe, 4)? != b"PE\0\0" {
+        return Err(String::from("not a dll, it has no PE header"));
+    }
+
+    let coff = pe + 4;
+    let machine = match reader.u16(coff)? {
+        IMAGE_FILE_MACHINE_I386 => Machine::X86,
+        IMAGE_FILE_MACHINE_AMD64 => Machine::X64,
+        IMAGE_FILE_MACHINE_ARM64 => Machine::Arm64,
+        other => Machine::Other(other),
+    };
+    let section_count = reader.u16(coff + 2)? as usize;
+    let optional_size = reader.u16(coff + 16)? as usize;
+
+    let optional = coff + 20;
+    let (directory_count, directories) = match reader.u16(optional)? {
+        PE32_MAGIC => (reader.u32(optional + 92)?, optional + 96),
+        PE32_PLUS_MAGIC => (reader.u32(optional + 108)?, optional + 112),
+        magic => return Err(format!("unknown optional header magic {magic:#x}")),
+    };
+
+    let section_table = optional + optional_size;
+    for i in 0..section_count {
+        let section = Reader::entry(section_table, i, 40)?;
+        reader.sections.push(Section {
+            virtual_size: reader.u32(section + 8)?,
+            virtual_address: reader.u32(section + 12)?,
+            raw_size: reader.u32(section + 16)?,
+            raw_pointer: reader.u32(section + 20)?,
+        });
+    }
+
+    // the export directory is the first data directory
+    let (export_rva, export_size) = if directory_count > 0 { (reader.u32(directories)?, reader.u32(directories + 4)?) } else { (0, 0) };
+    if export_rva == 0 {
+        return Ok(ExportTable { machine, dll_name: String::new(), exports: Vec::new() });
+    }
+
+    let directory = reader.rva_to_offset(export_rva)?;
+    let dll_name = reader.c_string(reader.u32(directory + 12)?)?;
+    let base = reader.u32(directory + 16)?;
+    let function_count = reader.u32(directory + 20)? as usize;
+    let name_count = reader.u32(directory + 24)? as usize;
+    // every function and name takes 4 bytes of the file, so counts it can't hold are corrupt rather than something to allocate for
+    if function_count > bytes.len() / 4 || name_count > bytes.len() / 4 {
+        return Err(format!("the export table claims {function_count} functions and {name_count} names, more than the file can hold"));
+    }
+    let functions = reader.rva_to_offset(reader.u32(directory + 28)?)?;
+
+    let mut names = vec![None; function_count];
+    if name_count > 0 {
+        let name_rvas = reader.rva_to_offset(reader.u32(directory + 32)?)?;
+        let name_indices = reader.rva_to_offset(reader.u32(directory + 36)?)?;
+        for i in 0..name_count {
+            let index = reader.u16(Reader::entry(name_indices, i, 2)?)? as usize;
+            let name = reader.c_string(reader.u32(Reader::entry(name_rvas, i, 4)?)?)?;
+            match names.get_mut(index) {
+                Some(slot) => *slot = Some(name),
+                None => return Err(format!("export {name} points past the end of the function table")),
+            }
+        }
+    }
+
+    let mut exports = Vec::new();
+    for (i, name) in names.into_iter().enumerate() {
+        let rva = reader.u32(Reader::entry(functions, i, 4)?)?;
+        if rva == 0 {
+            // gaps in the ordinals
+            continue;
+        }
+
+        // a function address inside the export directory is a forwarder string instead of code
+        let forward = if rva >= export_rva && rva - export_rva < export_size { Some(reader.c_string(rva)?) } else { None };
+        let ordinal = base.checked_add(i as u32).ok_or_else(|| format!("ordinal {base} + {i} is out of range"))?;
+        exports.push(Export { ordinal, name, forward });
+    }
+
+    Ok(ExportTable { machine, dll_name, exports })
+}
@@ -0,0 +1,106 @@
+use std::{env, hint::black_box, process::ExitCode, sync::{Mutex, mpsc::{self, Sender}}, thread, time::{Duration, Instant}};
+
+const DEFAULT_FRAMES: usize = 20000;
+/// Roughly what the game spends between presents outside of the overlay, so the other thread goes idle like it would
+const FRAME_GAP: Duration = Duration::from_micros(200);
+
+const USAGE: &str = "\
+A synthetic model of what handing the overlay's frame to another thread costs, not a measurement of BlueBrick.
+It doesn't load the loader, a renderer or imgui. Each frame of the old model makes three channel round trips to
+a thread that owns a counter, like new_frame, draw and post_draw did, and each frame of the new one locks a mutex
+around the same counter. The numbers only show the overhead of the thread hops on this machine.
+For the real cost in a game, see the frame times in BlueBrick's diagnostics window.
+
+Usage: bluebrick-frame-bench [FRAMES]
+
+FRAMES is how many frames to time for each model, 20000 by default";
+
+/// Stands in for the overlay's work, so both models do the same amount of it
+fn work(state: &mut u64) {
+    *state = black_box(*state + 1);
+}
+
+enum Event {
+    Run(fn(&mut u64), Sender<()>),
+}
+
+/// Three channel round trips to a thread that owns the state, like `OverlayHandle` used to do
+fn channel_round_trips(frames: usize) -> Vec<Duration> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut state = 0;
+        while let Ok(Event::Run(f, reply)) = rx.recv() {
+            f(&mut state);
+            let _ = reply.send(());
+        }
+    });
+
+    let mut times = Vec::with_capacity(frames);
+    for _ in 0..frames {
+        let started = Instant::now();
+        for _ in 0..3 {
+            let (reply_tx, reply_rx) = mpsc::channel();
+            tx.send(Event::Run(work, reply_tx)).unwrap();
+            reply_rx.recv().unwrap();
+        }
+        times.push(started.elapsed());
+        thread::sleep(FRAME_GAP);
+    }
+    times
+}
+
+/// One lock of the shared state on the render thread, like `BlueBrickHandle::with_overlay`
+fn render_thread_lock(frames: usize) -> Vec<Duration> {
+    let state = Mutex::new(0);
+
+    let mut times = Vec::with_capacity(frames);
+    for _ in 0..frames {
+        let started = Instant::now();
+        let mut state = state.lock().unwrap();
+        work(&mut state);
+        work(&mut state);
+        work(&mut state);
+        drop(state);
+        times.push(started.elapsed());
+        thread::sleep(FRAME_GAP);
+    }
+    times
+}
+
+fn micros(duration: Duration) -> String {
+    format!("{:.2} us", duration.as_secs_f64() * 1e6)
+}
+
+fn report(name: &str, mut times: Vec<Duration>) {
+    times.sort();
+    let mean = times.iter().sum::<Duration>() / times.len() as u32;
+    println!(
+        "{name}: mean {}, median {}, p99 {}, max {}",
+        micros(mean),
+        micros(times[times.len() / 2]),
+        micros(times[times.len() * 99 / 100]),
+        micros(times[times.len() - 1]),
+    );
+}
+
+fn main() -> ExitCode {
+    let frames = match env::args().nth(1).as_deref() {
+        None => DEFAULT_FRAMES,
+        Some("-h" | "--help") => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Some(frames) => match frames.parse::<usize>() {
+            Ok(frames) if frames > 0 => frames,
+            _ => {
+                eprintln!("{frames:?} is not a number of frames\n\n{USAGE}");
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+
+    println!("Timing {frames} synthetic frames of each model");
+    report("three channel round trips", channel_round_trips(frames));
+    report("render thread lock", render_thread_lock(frames));
+    ExitCode::SUCCESS
+}
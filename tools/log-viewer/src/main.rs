@@ -0,0 +1,217 @@
+use std::{env, io::{self, BufRead, BufReader, Read, Write}, net::TcpStream, process::ExitCode, thread, time::Duration};
+
+use serde_json::Value;
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:7345";
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+const RESET: &str = "\x1b[0m";
+
+const USAGE: &str = "\
+Shows the logs of a running game with BlueBrick's log server enabled,
+by adding server to log_sinks in bluebrick/config.toml or to BLUEBRICK_LOG_SINKS
+
+Usage: bluebrick-logs [ADDRESS] [OPTIONS]
+
+ADDRESS is host:port or unix:/path/to/socket, 127.0.0.1:7345 by default
+
+Options:
+  -l, --level LEVEL    Only show trace, debug, info, warning or error and above
+  -s, --source NAME    Only show this loader, library or mod, can be given more than once
+  -k, --kind KIND      Only show loader, library or mod records, can be given more than once
+  -g, --grep TEXT      Only show records containing the text, ignoring case
+      --no-history     Skip the records sent on connect from before the viewer started
+      --no-color       Print without colors
+      --once           Exit when the game closes instead of waiting for it to come back
+  -h, --help           Show this message";
+
+struct Options {
+    address: String,
+    min_rank: u8,
+    sources: Vec<String>,
+    kinds: Vec<String>,
+    grep: Option<String>,
+    history: bool,
+    color: bool,
+    once: bool,
+}
+
+fn severity_rank(severity: &str) -> Option<u8> {
+    match severity.to_ascii_lowercase().as_str() {
+        "trace" => Some(0),
+        "debug" => Some(1),
+        "info" => Some(2),
+        "warning" | "warn" => Some(3),
+        "error" => Some(4),
+        _ => None,
+    }
+}
+
+fn parse_args() -> Result<Option<Options>, String> {
+    let mut options = Options {
+        address: String::from(DEFAULT_ADDRESS),
+        min_rank: 0,
+        sources: Vec::new(),
+        kinds: Vec::new(),
+        grep: None,
+        history: true,
+        color: true,
+        once: false,
+    };
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{name} needs a value"));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-l" | "--level" => {
+                let level = value(&arg)?;
+                options.min_rank = severity_rank(&level).ok_or_else(|| format!("unknown level {level}"))?;
+            }
+            "-s" | "--source" => options.sources.push(value(&arg)?.to_ascii_lowercase()),
+            "-k" | "--kind" => options.kinds.push(value(&arg)?.to_ascii_lowercase()),
+            "-g" | "--grep" => options.grep = Some(value(&arg)?.to_ascii_lowercase()),
+            "--no-history" => options.history = false,
+            "--no-color" => options.color = false,
+            "--once" => options.once = true,
+            arg if arg.starts_with('-') => return Err(format!("unknown option {arg}")),
+            address => options.address = String::from(address),
+        }
+    }
+
+    Ok(Some(options))
+}
+
+fn connect(address: &str) -> io::Result<Box<dyn Read>> {
+    match address.strip_prefix("unix:") {
+        #[cfg(unix)]
+        Some(path) => Ok(Box::new(std::os::unix::net::UnixStream::connect(path)?)),
+        #[cfg(not(unix))]
+        Some(_) => Err(io::Error::new(io::ErrorKind::Unsupported, "unix sockets are only supported on unix")),
+        None => Ok(Box::new(TcpStream::connect(address)?)),
+    }
+}
+
+fn field<'a>(record: &'a Value, name: &str) -> &'a str {
+    record[name].as_str().unwrap_or_default()
+}
+
+fn shows(options: &Options, record: &Value) -> bool {
+    let rank = severity_rank(field(record, "severity")).unwrap_or(u8::MAX);
+    let name = field(record, "name").to_ascii_lowercase();
+    let kind = field(record, "kind").to_ascii_lowercase();
+
+    rank >= options.min_rank
+        && (options.history || !record["replayed"].as_bool().unwrap_or_default())
+        && (options.sources.is_empty() || options.sources.contains(&name))
+        && (options.kinds.is_empty() || options.kinds.contains(&kind))
+        && options.grep.as_ref().is_none_or(|grep| field(record, "message").to_ascii_lowercase().contains(grep))
+}
+
+/// Turns `#rrggbb` into a terminal color escape
+fn hex_to_ansi(hex: &str) -> Option<String> {
+    let hex = hex.strip_prefix('#')?;
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some(format!("\x1b[38;2;{};{};{}m", channel(0)?, channel(2)?, channel(4)?))
+}
+
+fn paint(options: &Options, text: &str, color: Option<&str>) -> String {
+    match color.and_then(hex_to_ansi) {
+        Some(escape) if options.color => format!("{escape}{text}{RESET}"),
+        _ => String::from(text),
+    }
+}
+
+/// Formats a record like the loader's own log lines
+fn format_record(options: &Options, record: &Value) -> String {
+    // the same colors the loader uses for each severity
+    let (time_color, text_color) = match field(record, "severity") {
+        "trace" => (Some("#696969"), Some("#808080")),
+        "debug" => (Some("#808080"), Some("#a9a9a9")),
+        "warning" => (Some("#daa520"), Some("#ffff00")),
+        "error" => (Some("#b22222"), Some("#ff0000")),
+        _ => (Some("#808080"), None),
+    };
+    let time = field(record, "time").get(11..19).unwrap_or_default();
+    let name_color = record["name_color"].as_str();
+
+    let mut lines = field(record, "message").split('\n');
+    let mut text = format!(
+        "{} [{}] [{}] {}",
+        paint(options, time, time_color),
+        paint(options, field(record, "kind"), text_color),
+        paint(options, field(record, "name"), name_color),
+        paint(options, lines.next().unwrap_or_default(), text_color),
+    );
+    for line in lines {
+        text += &format!("\n    {}", paint(options, line, text_color));
+    }
+    text
+}
+
+/// Prints records until the connection closes
+fn view(options: &Options) -> io::Result<()> {
+    let reader = BufReader::new(connect(&options.address)?);
+    eprintln!("Connected to {}", options.address);
+
+    let mut stdout = io::stdout().lock();
+    let mut replaying = false;
+    for line in reader.lines() {
+        let Ok(record) = serde_json::from_str::<Value>(&line?) else {
+            continue;
+        };
+
+        let replayed = record["replayed"].as_bool().unwrap_or_default();
+        if replaying && !replayed && options.history {
+            writeln!(stdout, "{}", paint(options, "---- live ----", Some("#808080")))?;
+        }
+        replaying = replayed;
+
+        if shows(options, &record) {
+            writeln!(stdout, "{}", format_record(options, &record))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args() {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut waiting = false;
+    loop {
+        let result = view(&options);
+        if options.once {
+            return match result {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => {
+                    eprintln!("Could not read from {}: {e}", options.address);
+                    ExitCode::FAILURE
+                }
+            };
+        }
+
+        match result {
+            Ok(()) => {
+                eprintln!("The game closed the connection, waiting for it to come back...");
+                waiting = true;
+            }
+            Err(e) if !waiting => {
+                eprintln!("Could not read from {}: {e}, waiting for the game...", options.address);
+                waiting = true;
+            }
+            Err(_) => {}
+        }
+
+        thread::sleep(RETRY_DELAY);
+    }
+}
@@ -0,0 +1,155 @@
+//! Writes a proxy crate that forwards every export to the real dll and starts BlueBrick
+
+use std::{fs, io, path::Path};
+
+use bluebrick_exports::pe::{Export, ExportTable, Machine};
+
+pub struct ProxyOptions {
+    /// The proxy's file name without `.dll`, like `d3d9`
+    pub name: String,
+    /// Where the exports are forwarded to, without `.dll`
+    pub forward_to: String,
+    /// `RequestedPlatform` variant
+    pub platform: &'static str,
+    /// `RequestedRenderer` variant
+    pub renderer: &'static str,
+    pub overlay: bool,
+    /// The `bluebrick-proxy` dependency line
+    pub bindings: String,
+}
+
+/// The linker argument that forwards one export, see `/EXPORT` in the msvc linker docs
+fn export_arg(export: &Export, forward_to: &str) -> String {
+    let target = match (&export.forward, &export.name) {
+        // already forwarded by the real dll, skipping a hop also avoids forwarding to a dll that forwards back
+        (Some(forward), _) => forward.clone(),
+        (None, Some(name)) => format!("{forward_to}.{name}"),
+        (None, None) => format!("{forward_to}.#{}", export.ordinal),
+    };
+
+    match &export.name {
+        Some(name) => format!("{name}={target},@{}", export.ordinal),
+        None => format!("ordinal{0}={target},@{0},NONAME", export.ordinal),
+    }
+}
+
+fn target_triple(machine: Machine) -> Option<&'static str> {
+    match machine {
+        Machine::X86 => Some("i686-pc-windows-msvc"),
+        Machine::X64 => Some("x86_64-pc-windows-msvc"),
+        Machine::Arm64 => Some("aarch64-pc-windows-msvc"),
+        Machine::Other(_) => None,
+    }
+}
+
+/// The crate name for a dll name, which has to be an identifier so the built dll keeps the same name
+pub fn crate_name(name: &str) -> String {
+    name.chars().map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' }).collect()
+}
+
+fn cargo_toml(options: &ProxyOptions, source: &str) -> String {
+    format!(
+        "\
+# Generated by bluebrick-proxy-gen from {source}
+[package]
+name = \"{name}-proxy\"
+version = \"0.1.0\"
+edition = \"2024\"
+
+[dependencies]
+{bindings}
+
+[lib]
+crate-type = [ \"cdylib\" ]
+name = \"{name}\"
+
+# keeps the proxy out of any workspace it was generated in
+[workspace]
+",
+        name = crate_name(&options.name),
+        bindings = options.bindings,
+    )
+}
+
+fn build_rs(options: &ProxyOptions, table: &ExportTable) -> String {
+    let exports = table.exports.iter().map(|export| format!("    {:?},\n", export_arg(export, &options.forward_to))).collect::<String>();
+
+    format!(
+        "\
+// Generated by bluebrick-proxy-gen, forwards all {count} exports of {name}.dll to {forward_to:?}
+
+const EXPORTS: &[&str] = &[
+{exports}];
+
+fn main() {{
+    if std::env::var(\"CARGO_CFG_TARGET_ENV\").as_deref() != Ok(\"msvc\") {{
+        panic!(\"forwarding the exports needs the msvc linker\");
+    }}
+
+    for export in EXPORTS {{
+        println!(\"cargo:rustc-cdylib-link-arg=/EXPORT:{{export}}\");
+    }}
+}}
+",
+        count = table.exports.len(),
+        name = options.name,
+        forward_to = options.forward_to,
+    )
+}
+
+fn lib_rs(options: &ProxyOptions) -> String {
+    format!(
+        "\
+//! Generated by bluebrick-proxy-gen. build.rs forwards every export of {name}.dll to the real one,
+//! this only starts BlueBrick when the game loads the proxy.
+
+use std::ffi::c_void;
+
+use bluebrick_proxy::{{Config, RequestedPlatform, RequestedRenderer, load_bluebrick}};
+
+const DLL_PROCESS_ATTACH: u32 = 1;
+
+const CONFIG: Config = Config {{
+    overlay_enabled: {overlay},
+    ..Config::new(RequestedPlatform::{platform}, RequestedRenderer::{renderer})
+}};
+
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+extern \"system\" fn DllMain(_module: *mut c_void, reason: u32, _reserved: *mut c_void) -> i32 {{
+    // bluebrick has to hook the graphics apis before the game gets to use them, so this can't wait for a thread
+    if reason == DLL_PROCESS_ATTACH {{
+        load_bluebrick(CONFIG);
+    }}
+    1
+}}
+",
+        name = options.name,
+        overlay = options.overlay,
+        platform = options.platform,
+        renderer = options.renderer,
+    )
+}
+
+fn cargo_config(machine: Machine) -> Option<String> {
+    target_triple(machine).map(|triple| format!("# the proxy has to match the architecture of {machine} games\n[build]\ntarget = \"{triple}\"\n"))
+}
+
+/// Writes the crate to `out`, which must not exist yet unless `force` is set
+pub fn write_crate(out: &Path, options: &ProxyOptions, table: &ExportTable, source: &str, force: bool) -> io::Result<()> {
+    if out.exists() && !force {
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} already exists, pass --force to overwrite it", out.display())));
+    }
+
+    fs::create_dir_all(out.join("src"))?;
+    fs::write(out.join("Cargo.toml"), cargo_toml(options, source))?;
+    fs::write(out.join("build.rs"), build_rs(options, table))?;
+    fs::write(out.join("src").join("lib.rs"), lib_rs(options))?;
+
+    if let Some(config) = cargo_config(table.machine) {
+        fs::create_dir_all(out.join(".cargo"))?;
+        fs::write(out.join(".cargo").join("config.toml"), config)?;
+    }
+
+    Ok(())
+}
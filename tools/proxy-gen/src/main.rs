@@ -0,0 +1,204 @@
+mod generate;
+
+use std::{env, fs, path::{Path, PathBuf}, process::ExitCode};
+
+use bluebrick_exports::pe;
+use generate::ProxyOptions;
+
+const DEFAULT_BINDINGS: &str = "bluebrick-proxy = { git = \"https://github.com/XdotCore/BlueBrick\" }";
+
+const USAGE: &str = "\
+Generates a proxy dll crate that forwards every export of a real dll and loads BlueBrick
+
+Usage: bluebrick-proxy-gen DLL [OPTIONS]
+
+DLL is the real dll to copy the exports of, like a d3d9.dll taken from the game's System32 or SysWOW64
+
+Options:
+  -o, --out DIR          Where to write the crate, NAME-proxy by default
+  -n, --name NAME        The proxy's file name without .dll, the real dll's by default
+      --forward-to DLL   The dll the exports are forwarded to, C:\\Windows\\System32\\NAME by default
+  -p, --platform NAME    The platform BlueBrick starts with, win32 or auto (default)
+  -r, --renderer NAME    The renderer BlueBrick starts with, dx9 or auto (default)
+      --no-overlay       Start BlueBrick without its overlay
+      --bindings PATH    Use a local rust-proxy-bindings folder instead of the git repository
+      --force            Overwrite the crate if it already exists
+      --list             Only print the exports
+  -h, --help             Show this message";
+
+struct Options {
+    dll: PathBuf,
+    out: Option<PathBuf>,
+    name: Option<String>,
+    forward_to: Option<String>,
+    platform: &'static str,
+    renderer: &'static str,
+    overlay: bool,
+    bindings: Option<PathBuf>,
+    force: bool,
+    list: bool,
+}
+
+/// The same names `RequestedPlatform::from_name` takes, without needing the bindings on this side
+fn platform_variant(name: &str) -> Option<&'static str> {
+    match name.to_ascii_lowercase().as_str() {
+        "win32" | "windows" => Some("Win32"),
+        "auto" => Some("Auto"),
+        _ => None,
+    }
+}
+
+/// The same names `RequestedRenderer::from_name` takes, without needing the bindings on this side
+fn renderer_variant(name: &str) -> Option<&'static str> {
+    match name.to_ascii_lowercase().as_str() {
+        "dx9" | "d3d9" | "directx9" => Some("DX9"),
+        "auto" => Some("Auto"),
+        _ => None,
+    }
+}
+
+fn parse_args() -> Result<Option<Options>, String> {
+    let mut dll = None;
+    let mut options = Options {
+        dll: PathBuf::new(),
+        out: None,
+        name: None,
+        forward_to: None,
+        platform: "Auto",
+        renderer: "Auto",
+        overlay: true,
+        bindings: None,
+        force: false,
+        list: false,
+    };
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{name} needs a value"));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-o" | "--out" => options.out = Some(PathBuf::from(value(&arg)?)),
+            "-n" | "--name" => options.name = Some(value(&arg)?),
+            "--forward-to" => options.forward_to = Some(value(&arg)?),
+            "-p" | "--platform" => {
+                let platform = value(&arg)?;
+                options.platform = platform_variant(&platform).ok_or_else(|| format!("unknown platform {platform}"))?;
+            }
+            "-r" | "--renderer" => {
+                let renderer = value(&arg)?;
+                options.renderer = renderer_variant(&renderer).ok_or_else(|| format!("unknown renderer {renderer}"))?;
+            }
+            "--no-overlay" => options.overlay = false,
+            "--bindings" => options.bindings = Some(PathBuf::from(value(&arg)?)),
+            "--force" => options.force = true,
+            "--list" => options.list = true,
+            arg if arg.starts_with('-') => return Err(format!("unknown option {arg}")),
+            path if dll.is_none() => dll = Some(PathBuf::from(path)),
+            path => return Err(format!("unexpected argument {path}")),
+        }
+    }
+
+    options.dll = dll.ok_or("no dll was given")?;
+    Ok(Some(options))
+}
+
+fn strip_dll(name: &str) -> &str {
+    match name.len().checked_sub(4) {
+        Some(stem) if name.is_char_boundary(stem) && name[stem..].eq_ignore_ascii_case(".dll") => &name[..stem],
+        _ => name,
+    }
+}
+
+fn list(table: &pe::ExportTable) {
+    println!("{} ({}), {} exports", table.dll_name, table.machine, table.exports.len());
+    for export in &table.exports {
+        let name = export.name.as_deref().unwrap_or("(by ordinal only)");
+        match &export.forward {
+            Some(forward) => println!("{:>6}  {name} -> {forward}", export.ordinal),
+            None => println!("{:>6}  {name}", export.ordinal),
+        }
+    }
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let bytes = fs::read(&options.dll).map_err(|e| format!("could not read {}: {e}", options.dll.display()))?;
+    let table = pe::read_exports(&bytes).map_err(|e| format!("could not read the exports of {}: {e}", options.dll.display()))?;
+
+    if options.list {
+        list(&table);
+        return Ok(());
+    }
+
+    if table.exports.is_empty() {
+        return Err(format!("{} has no exports to forward", options.dll.display()));
+    }
+
+    let file_name = options.dll.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let name = options.name.unwrap_or_else(|| String::from(strip_dll(&file_name)));
+    let forward_to = match options.forward_to {
+        Some(forward_to) => String::from(strip_dll(&forward_to)),
+        // 32 bit games are redirected to SysWOW64 by windows
+        None => format!("C:\\Windows\\System32\\{name}"),
+    };
+    let bindings = match options.bindings {
+        Some(path) => {
+            let path = path.canonicalize().map_err(|e| format!("could not find the bindings at {}: {e}", path.display()))?;
+            format!("bluebrick-proxy = {{ path = {:?} }}", path.to_string_lossy())
+        }
+        None => String::from(DEFAULT_BINDINGS),
+    };
+
+    let out = options.out.unwrap_or_else(|| PathBuf::from(format!("{name}-proxy")));
+    let proxy = ProxyOptions {
+        name,
+        forward_to,
+        platform: options.platform,
+        renderer: options.renderer,
+        overlay: options.overlay,
+        bindings,
+    };
+    generate::write_crate(&out, &proxy, &table, &file_name, options.force).map_err(|e| format!("could not write the crate: {e}"))?;
+
+    let named = table.exports.iter().filter(|export| export.name.is_some()).count();
+    let forwarded = table.exports.iter().filter(|export| export.forward.is_some()).count();
+    println!(
+        "Wrote {} for {} {}: {} exports ({named} named, {} by ordinal only, {forwarded} already forwarded by the real dll)",
+        out.display(),
+        table.machine,
+        file_name,
+        table.exports.len(),
+        table.exports.len() - named,
+    );
+
+    let built = generate::crate_name(&proxy.name);
+    if built != proxy.name {
+        println!("The dll will build as {built}.dll, rename it to {}.dll", proxy.name);
+    }
+    if !Path::new(&out).join(".cargo").exists() {
+        println!("Build it for the same architecture as the game with --target");
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args() {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Reads the exports of a small checked in dll and of broken copies of it
+
+use std::{fs, path::{Path, PathBuf}, process::Command};
+
+/// A 64 bit dll named exports.dll with five functions from ordinal 1:
+/// `Foo`, one by ordinal only, `Bar` forwarded to `NTDLL.RtlBar`, one by ordinal only forwarded to `OTHER.#12`, and a gap
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/exports.dll");
+
+/// The export directory starts the only section, which is at 0x200 in the file
+const EXPORT_DIRECTORY: usize = 0x200;
+
+fn list(dll: &Path) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_bluebrick-proxy-gen"))
+        .arg(dll)
+        .arg("--list")
+        .output()
+        .expect("could not run bluebrick-proxy-gen");
+    let text = String::from_utf8_lossy(&output.stdout).to_string() + &String::from_utf8_lossy(&output.stderr);
+    (output.status.success(), text)
+}
+
+/// Writes a copy of the fixture with a u32 of the export directory replaced
+fn broken_copy(name: &str, field: usize, value: u32) -> PathBuf {
+    let mut bytes = fs::read(FIXTURE).unwrap();
+    let offset = EXPORT_DIRECTORY + field;
+    bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::write(&path, bytes).unwrap();
+    path
+}
+
+#[test]
+fn lists_named_ordinal_and_forwarded_exports() {
+    let (success, output) = list(Path::new(FIXTURE));
+    assert!(success, "listing failed:\n{output}");
+
+    let lines = output.lines().map(str::trim).collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        [
+            "exports.dll (x64), 4 exports",
+            "1  Foo",
+            "2  (by ordinal only)",
+            "3  Bar -> NTDLL.RtlBar",
+            "4  (by ordinal only) -> OTHER.#12",
+        ],
+    );
+}
+
+#[test]
+fn rejects_counts_the_file_cannot_hold() {
+    let (success, output) = list(&broken_copy("huge-function-count.dll", 20, u32::MAX));
+    assert!(!success, "a huge function count was accepted:\n{output}");
+    assert!(output.contains("more than the file can hold"), "{output}");
+}
+
+#[test]
+fn rejects_ordinals_past_the_end() {
+    let (success, output) = list(&broken_copy("overflowing-base.dll", 16, u32::MAX));
+    assert!(!success, "an overflowing ordinal base was accepted:\n{output}");
+    assert!(output.contains("out of range"), "{output}");
+}